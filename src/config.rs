@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use datarace_plugin_api::wrappers::PluginHandle;
+
+/// Environment variable to have the telemetry follow the car the camera is focused on
+const ENV_FOLLOW_CAMERA: &'static str = "DR_RF2_FOLLOW_CAMERA";
+/// Environment variable to request the Weather page from the start, instead of waiting for the use_weather action
+const ENV_WEATHER: &'static str = "DR_RF2_WEATHER";
+/// Environment variable to sample ForceFeedback from the start, instead of waiting for the use_ffb action
+const ENV_FFB: &'static str = "DR_RF2_FFB";
+/// Environment variable with the path of the shm-bridge-rf2.exe to install into the prefix
+const ENV_BRIDGE_SOURCE: &'static str = "DR_RF2_BRIDGE_SOURCE";
+/// Environment variable with the path of the rFactor2SharedMemoryMapPlugin64.dll to install into the game
+const ENV_PLUGIN_SOURCE: &'static str = "DR_RF2_PLUGIN_SOURCE";
+/// Environment variable selecting how the bridge is launched: auto, native, flatpak or a custom command
+const ENV_LAUNCHER: &'static str = "DR_RF2_LAUNCHER";
+
+/// User configuration
+/// As there is no settings storage for plugins (yet) this is read from environment variables on init
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Config {
+    /// Telemetry follows the vehicle the camera is focused on (requires the Graphics page),
+    /// instead of the player vehicle
+    pub follow_camera: bool,
+    /// Weather target properties are used from the start (requires the Weather page)
+    pub weather: bool,
+    /// ForceFeedback properties are used from the start
+    pub ffb: bool,
+    /// Bridge executable that gets installed into the prefix, if it is missing or differs
+    pub bridge_source: Option<PathBuf>,
+    /// rF2SharedMemoryMap plugin dll that gets installed into the game, if it is missing
+    pub plugin_source: Option<PathBuf>,
+    /// How protontricks-launch is run
+    pub launcher: LauncherConfig,
+}
+
+/// Which protontricks to use for launching the bridge
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum LauncherConfig {
+    /// Native if found, else Flatpak
+    #[default]
+    Auto,
+    Native,
+    Flatpak,
+    /// Command (split on whitespace) used in place of protontricks-launch
+    Custom(Vec<String>),
+}
+
+impl Config {
+    pub(crate) fn from_env(handle: &PluginHandle) -> Self {
+        let config = Config {
+            follow_camera: read_bool(ENV_FOLLOW_CAMERA),
+            weather: read_bool(ENV_WEATHER),
+            ffb: read_bool(ENV_FFB),
+            bridge_source: read_path(ENV_BRIDGE_SOURCE),
+            plugin_source: read_path(ENV_PLUGIN_SOURCE),
+            launcher: read_launcher(ENV_LAUNCHER),
+        };
+
+        handle.log_info(format!("Config: {:?}", config));
+        config
+    }
+}
+
+/// Flags are set if they are not empty, 0, false, or off
+fn read_bool(name: &str) -> bool {
+    match std::env::var(name) {
+        Ok(value) => !matches!(value.trim().to_lowercase().as_str(), "" | "0" | "false" | "off" | "no"),
+        Err(_) => false
+    }
+}
+
+fn read_path(name: &str) -> Option<PathBuf> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => Some(PathBuf::from(value.trim())),
+        _ => None
+    }
+}
+
+fn read_launcher(name: &str) -> LauncherConfig {
+    let value = std::env::var(name).unwrap_or_default();
+
+    match value.trim().to_lowercase().as_str() {
+        "" | "auto" => LauncherConfig::Auto,
+        "native" => LauncherConfig::Native,
+        "flatpak" => LauncherConfig::Flatpak,
+        _ => LauncherConfig::Custom(value.split_whitespace().map(|s| s.to_string()).collect())
+    }
+}
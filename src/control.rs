@@ -0,0 +1,145 @@
+use std::{collections::VecDeque, sync::{atomic::{AtomicI32, Ordering}, Mutex}, time::{Duration, Instant}};
+
+use datarace_plugin_api::{macros::generate_action_code, wrappers::{Action, PluginHandle}};
+
+use crate::{config::Config, data::{PageHWControl, RF2SubscribedBuffer, HW_CONTROL_LAYOUT_VERSION}, share::InputBuffer};
+
+/// The plugin reads the HWControl buffer at 5Hz, anything written faster would overwrite the previous input
+const HW_CONTROL_INTERVAL: Duration = Duration::from_millis(200);
+/// Controls waiting to be written, at HW_CONTROL_INTERVAL that is over a second behind already
+const MAX_QUEUED_CONTROLS: usize = 8;
+
+/// Actions we accept, and the name of the rF2 control they press
+const CONTROLS: [(u64, &'static str); 6] = [
+    (generate_action_code!("pit_menu_up"), "PitMenuUp"),
+    (generate_action_code!("pit_menu_down"), "PitMenuDown"),
+    (generate_action_code!("pit_menu_increment"), "PitMenuIncrementValue"),
+    (generate_action_code!("pit_menu_decrement"), "PitMenuDecrementValue"),
+    (generate_action_code!("pit_request"), "PitRequest"),
+    (generate_action_code!("toggle_headlights"), "Headlights"),
+];
+
+/// Actions to tell us the properties of a page the plugin doesn't write by default are used.
+/// DataRace does not report which properties are read, so whoever uses them (e.g. a dashboard on load)
+/// sends these, and the updater requests the page through the PluginControl buffer
+const BUFFER_USES: [(u64, RF2SubscribedBuffer); 3] = [
+    (generate_action_code!("use_graphics"), RF2SubscribedBuffer::Graphics),
+    (generate_action_code!("use_weather"), RF2SubscribedBuffer::Weather),
+    // Subscribed by default, but only sampled (at 400Hz) once used
+    (generate_action_code!("use_ffb"), RF2SubscribedBuffer::ForceFeedback),
+];
+
+/// Queue of controls to press, filled by actions on the main thread and drained by the updater
+pub(crate) type ControlQueue = Mutex<VecDeque<&'static str>>;
+
+/// RF2SubscribedBuffer mask of the pages in use, grown by actions, requested (and for ForceFeedback sampled) by the updater
+pub(crate) type BufferUses = AtomicI32;
+
+/// Pages in use from the start, the follow camera needs Graphics, and the config can preset Weather and ForceFeedback
+pub(crate) fn initial_buffer_uses(config: &Config) -> BufferUses {
+    let mut mask = 0;
+    if config.follow_camera {
+        mask |= RF2SubscribedBuffer::Graphics as i32;
+    }
+    if config.weather {
+        mask |= RF2SubscribedBuffer::Weather as i32;
+    }
+    if config.ffb {
+        mask |= RF2SubscribedBuffer::ForceFeedback as i32;
+    }
+
+    BufferUses::new(mask)
+}
+
+/// Queues the control, or marks the page as used, for the received action
+pub(crate) fn handle_action(handle: &PluginHandle, action: &Action, queue: &ControlQueue, uses: &BufferUses) {
+    let code = action.get_action_code();
+
+    if let Some((_, control)) = CONTROLS.iter().find(|(c, _)| *c == code) {
+        match queue.lock() {
+            Ok(mut queue) if queue.len() < MAX_QUEUED_CONTROLS => queue.push_back(control),
+            Ok(_) => handle.log_error(format!("Control queue full, dropping {control}")),
+            Err(_) => handle.log_error("Control queue poisoned, dropping action"),
+        }
+    } else if let Some((_, buffer)) = BUFFER_USES.iter().find(|(c, _)| *c == code) {
+        uses.fetch_or(*buffer as i32, Ordering::AcqRel);
+    } else {
+        handle.log_error(format!("Unknown action {code} received"));
+    }
+}
+
+/// Paces the writes into the HWControl buffer
+pub(crate) struct ControlWriter {
+    next_write: Instant,
+    /// Version of the last write, checked to still be there before the next one
+    last_write: Option<u32>,
+    /// The bridge replaced a write with the game's buffer, so controls don't reach the game
+    writes_lost: bool,
+}
+
+impl ControlWriter {
+    pub(crate) fn new(queue: &ControlQueue) -> Self {
+        // Inputs queued while the game was not running are stale
+        if let Ok(mut queue) = queue.lock() {
+            queue.clear();
+        }
+
+        ControlWriter { next_write: Instant::now(), last_write: None, writes_lost: false }
+    }
+
+    /// Sends the next queued control, if the previous one had time to be picked up.
+    /// Without a buffer (or once writes turned out to not reach the game) queued controls are dropped
+    pub(crate) fn process(&mut self, handle: &PluginHandle, buffer: Option<&InputBuffer<PageHWControl>>, queue: &ControlQueue) {
+        let now = Instant::now();
+        if now < self.next_write {
+            return;
+        }
+
+        let buffer = match buffer {
+            Some(buffer) if !self.writes_lost => buffer,
+            _ => {
+                drop_queued(handle, queue, if self.writes_lost { "the bridge does not carry them to the game" } else { "HWControl is not mounted" });
+                return;
+            }
+        };
+
+        if let Some(version) = self.last_write.take() {
+            if !buffer.holds(version) {
+                handle.log_error("HWControl write was replaced by the game's buffer, shm-bridge does not carry writes back to the game, controls are disabled");
+                self.writes_lost = true;
+                return;
+            }
+        }
+
+        let control = match queue.lock() {
+            Ok(mut queue) => queue.pop_front(),
+            Err(_) => None
+        };
+
+        if let Some(control) = control {
+            let version = buffer.write(HW_CONTROL_LAYOUT_VERSION, |page| {
+                let mut name = [0; 96];
+                // Last byte stays the null terminator
+                let len = control.len().min(name.len() - 1);
+                name[..len].copy_from_slice(&control.as_bytes()[..len]);
+
+                page.control_name = name;
+                page.ret_val = 1.0;
+            });
+
+            self.last_write = Some(version);
+            self.next_write = now + HW_CONTROL_INTERVAL;
+        }
+    }
+}
+
+fn drop_queued(handle: &PluginHandle, queue: &ControlQueue, reason: &str) {
+    let dropped = match queue.lock() {
+        Ok(mut queue) => queue.drain(..).collect::<Vec<_>>(),
+        Err(_) => return
+    };
+
+    if !dropped.is_empty() {
+        handle.log_error(format!("Dropping controls {}, {reason}", dropped.join(", ")));
+    }
+}
@@ -34,6 +34,9 @@ type String12 = [u8; 12];
 type Garbage = u8;
 
 
+// Enums stored in the pages as raw bytes, as the game can write any value into them
+// (and a byte outside the variants would be UB as the enum). from_raw validates them
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CharBool {
@@ -42,6 +45,17 @@ pub enum CharBool {
     Unkown
 }
 
+impl CharBool {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(CharBool::False),
+            1 => Some(CharBool::True),
+            2 => Some(CharBool::Unkown),
+            _ => None
+        }
+    }
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RF2RearFlapLegalStatus {
@@ -50,6 +64,17 @@ pub enum RF2RearFlapLegalStatus {
   Allowed = 2
 }
 
+impl RF2RearFlapLegalStatus {
+    pub fn from_raw(status: u8) -> Option<Self> {
+        match status {
+            0 => Some(RF2RearFlapLegalStatus::Disallowed),
+            1 => Some(RF2RearFlapLegalStatus::DetectedButNotAllowedYet),
+            2 => Some(RF2RearFlapLegalStatus::Allowed),
+            _ => None
+        }
+    }
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RF2IgnitionStarterStatus {
@@ -58,6 +83,150 @@ pub enum RF2IgnitionStarterStatus {
   IgnitionAndStarter = 2
 }
 
+impl RF2IgnitionStarterStatus {
+    pub fn from_raw(status: u8) -> Option<Self> {
+        match status {
+            0 => Some(RF2IgnitionStarterStatus::Off),
+            1 => Some(RF2IgnitionStarterStatus::Ignition),
+            2 => Some(RF2IgnitionStarterStatus::IgnitionAndStarter),
+            _ => None
+        }
+    }
+}
+
+/// Decoded ScoringInfo session (0=testday 1-4=practice 5-8=qual 9=warmup 10-13=race)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RF2SessionType {
+    TestDay,
+    Practice(u8),
+    Qualifying(u8),
+    Warmup,
+    Race(u8)
+}
+
+impl RF2SessionType {
+    pub fn from_raw(session: i32) -> Option<Self> {
+        match session {
+            0 => Some(RF2SessionType::TestDay),
+            1..=4 => Some(RF2SessionType::Practice(session as u8)),
+            5..=8 => Some(RF2SessionType::Qualifying(session as u8 - 4)),
+            9 => Some(RF2SessionType::Warmup),
+            10..=13 => Some(RF2SessionType::Race(session as u8 - 9)),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RF2SessionType::TestDay => "TestDay",
+            RF2SessionType::Practice(_) => "Practice",
+            RF2SessionType::Qualifying(_) => "Qualifying",
+            RF2SessionType::Warmup => "Warmup",
+            RF2SessionType::Race(_) => "Race",
+        }
+    }
+
+    /// 1-based number of the session within it's type (Practice 1-4, Qualifying 1-4, Race 1-4)
+    pub fn number(&self) -> u8 {
+        match self {
+            RF2SessionType::Practice(n) | RF2SessionType::Qualifying(n) | RF2SessionType::Race(n) => *n,
+            _ => 1
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RF2GamePhase {
+    Garage = 0,
+    WarmUp = 1,
+    GridWalk = 2,
+    Formation = 3,
+    Countdown = 4,
+    GreenFlag = 5,
+    FullCourseYellow = 6,
+    SessionStopped = 7,
+    SessionOver = 8,
+    PausedOrHeartbeat = 9
+}
+
+impl RF2GamePhase {
+    pub fn from_raw(phase: u8) -> Option<Self> {
+        match phase {
+            0 => Some(RF2GamePhase::Garage),
+            1 => Some(RF2GamePhase::WarmUp),
+            2 => Some(RF2GamePhase::GridWalk),
+            3 => Some(RF2GamePhase::Formation),
+            4 => Some(RF2GamePhase::Countdown),
+            5 => Some(RF2GamePhase::GreenFlag),
+            6 => Some(RF2GamePhase::FullCourseYellow),
+            7 => Some(RF2GamePhase::SessionStopped),
+            8 => Some(RF2GamePhase::SessionOver),
+            9 => Some(RF2GamePhase::PausedOrHeartbeat),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RF2GamePhase::Garage => "Garage",
+            RF2GamePhase::WarmUp => "WarmUp",
+            RF2GamePhase::GridWalk => "GridWalk",
+            RF2GamePhase::Formation => "Formation",
+            RF2GamePhase::Countdown => "Countdown",
+            RF2GamePhase::GreenFlag => "GreenFlag",
+            RF2GamePhase::FullCourseYellow => "FullCourseYellow",
+            RF2GamePhase::SessionStopped => "SessionStopped",
+            RF2GamePhase::SessionOver => "SessionOver",
+            RF2GamePhase::PausedOrHeartbeat => "PausedOrHeartbeat",
+        }
+    }
+}
+
+/// Full course yellow states, also used for the local sector yellows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RF2YellowFlagState {
+    Invalid = -1,
+    NoFlag = 0,
+    Pending = 1,
+    PitClosed = 2,
+    PitLeadLap = 3,
+    PitOpen = 4,
+    LastLap = 5,
+    Resume = 6,
+    RaceHalt = 7
+}
+
+impl RF2YellowFlagState {
+    pub fn from_raw(state: i8) -> Option<Self> {
+        match state {
+            -1 => Some(RF2YellowFlagState::Invalid),
+            0 => Some(RF2YellowFlagState::NoFlag),
+            1 => Some(RF2YellowFlagState::Pending),
+            2 => Some(RF2YellowFlagState::PitClosed),
+            3 => Some(RF2YellowFlagState::PitLeadLap),
+            4 => Some(RF2YellowFlagState::PitOpen),
+            5 => Some(RF2YellowFlagState::LastLap),
+            6 => Some(RF2YellowFlagState::Resume),
+            7 => Some(RF2YellowFlagState::RaceHalt),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RF2YellowFlagState::Invalid => "Invalid",
+            RF2YellowFlagState::NoFlag => "NoFlag",
+            RF2YellowFlagState::Pending => "Pending",
+            RF2YellowFlagState::PitClosed => "PitClosed",
+            RF2YellowFlagState::PitLeadLap => "PitLeadLap",
+            RF2YellowFlagState::PitOpen => "PitOpen",
+            RF2YellowFlagState::LastLap => "LastLap",
+            RF2YellowFlagState::Resume => "Resume",
+            RF2YellowFlagState::RaceHalt => "RaceHalt",
+        }
+    }
+}
+
 #[repr(C, packed(4))]
 #[derive(Copy, Clone, Debug, Default)]
 pub struct PageVec3 {
@@ -230,8 +399,10 @@ pub struct PageVehicleTelemetry {
     pub front_flap_activated: u8,
     /// whether rear flap is activated    
     pub rear_flap_activated: u8,
-    pub rear_flap_legal_status: RF2RearFlapLegalStatus,
-    pub ignition_starter: RF2IgnitionStarterStatus,
+    /// RF2RearFlapLegalStatus, use rear_flap_legal_status()
+    pub rear_flap_legal_status: u8,
+    /// RF2IgnitionStarterStatus, use ignition_starter()
+    pub ignition_starter: u8,
 
     /// name of front tire compound    
     pub front_tire_compound_name: String18,
@@ -265,6 +436,16 @@ pub struct PageVehicleTelemetry {
     pub wheels: [PageWheelTelemetry; 4],
 }
 
+impl PageVehicleTelemetry {
+    pub fn rear_flap_legal_status(&self) -> Option<RF2RearFlapLegalStatus> {
+        RF2RearFlapLegalStatus::from_raw(self.rear_flap_legal_status)
+    }
+
+    pub fn ignition_starter(&self) -> Option<RF2IgnitionStarterStatus> {
+        RF2IgnitionStarterStatus::from_raw(self.ignition_starter)
+    }
+}
+
 #[repr(C, packed(4))]
 #[derive(Copy, Clone, Debug)]
 pub struct PageWheelTelemetry {
@@ -709,18 +890,172 @@ pub struct PageMultiRules {
     ///
     /// 0 means unknown (whole buffer should be considered as updated).
     pub bytes_updated_hint: i32,
+
+    pub multi_session_rules: PageMultiSessionRules,
+    pub participants: [PageMultiSessionParticipant; MAX_MAPPED_VEHICLES],
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageMultiSessionRules {
+    // input only
+    /// current session (0=testday 1-4=practice 5-8=qual 9=warmup 10-13=race)
+    pub session: i32,
+    /// slot ID of someone who just joined, or -2 requesting to update qual order, -1 = invalid
+    pub special_slot_id: i32,
+    /// track type from GDB
+    pub track_type: String32,
+    /// number of participants (vehicles)
+    pub num_participants: i32,
+    /// array of participants (vehicles)
+    pointer1: [Garbage; 8],
+
+    // input/output
+    /// number of qualifying sessions configured
+    pub num_qual_sessions: i32,
+    /// number of race sessions configured
+    pub num_race_sessions: i32,
+    /// maximum laps allowed in current session (LONG_MAX = unlimited) (note: cannot currently edit in *race*)
+    pub max_laps: i32,
+    /// maximum time allowed in current session (LONG_MAX = unlimited) (note: cannot currently edit in *race*)
+    pub max_seconds: i32,
+    /// untranslated name override for session (usually use mNamePrefix in GDB file)
+    pub name: String32,
+
+    /// future expansion
+    expansion: [Garbage; 256],
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageMultiSessionParticipant {
+    // input only
+    /// slot ID
+    pub id: i32,
+    /// driver name
+    pub driver_name: String32,
+    /// vehicle name
+    pub vehicle_name: String64,
+    /// coded upgrades
+    pub upgrade_pack: [u8; 16],
+
+    /// may be used for session qualifying order
+    pub best_practice_time: f32,
+    /// once qualifying begins, this becomes valid and ranks participants according to practice time if possible
+    pub qual_participant_index: i32,
+    /// best qualification time in up to 4 qual sessions
+    pub qualification_time: [f32; 4],
+    /// final race place in up to 4 race sessions
+    pub final_race_place: [f32; 4],
+    /// final race time in up to 4 race sessions
+    pub final_race_time: [f32; 4],
+
+    // input/output
+    /// whether vehicle is allowed to participate in current session
+    pub server_scored: u8,
+    /// 1-based grid position for current race session (or upcoming race session if it is currently warmup), or -1 if currently disallowed
+    pub grid_position: i32,
+    /// 0-based pit index (or -1 if unassigned), may be required for the current race session
+    pub pit_index: i32,
+    /// 0-based garage index (or -1 if unassigned), required for practice/qualifying sessions
+    pub garage_index: i32,
+
+    /// future expansion
+    expansion: [Garbage; 128],
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageGraphics {
+    pub header: PageHeader,
+
+    pub graphics_info: PageGraphicsInfo,
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageGraphicsInfo {
+    /// camera position
+    pub cam_pos: PageVec3,
+    /// rows of orientation matrix (use TelemQuat conversions if desired), also converts local
+    pub cam_ori: [PageVec3; 3],
+    /// app handle
+    hwnd: [Garbage; 8],
+
+    /// ambient color
+    pub ambient_red: f64,
+    pub ambient_green: f64,
+    pub ambient_blue: f64,
+
+    /// slot ID being viewed (-1 if invalid)
+    pub id: i32,
+
+    /// see CameraControlInfoV01 for values
+    pub camera_type: i32,
+
+    /// for future use (possibly camera name)
+    expansion: [Garbage; 128],
 }
 
 #[repr(C, packed(4))]
 #[derive(Copy, Clone, Debug)]
 pub struct PagePitInfo {
     pub header: PageHeader,
+
+    pub pit_menu: PagePitMenu,
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PagePitMenu {
+    /// index of the current category
+    pub category_index: i32,
+    /// name of the current category (untranslated)
+    pub category_name: String32,
+
+    /// index of the current choice (within the current category)
+    pub choice_index: i32,
+    /// name of the current choice (may have some translated words)
+    pub choice_string: String32,
+    /// total number of choices (0 <= choice_index < num_choices)
+    pub num_choices: i32,
+
+    /// for future use
+    expansion: [Garbage; 256],
 }
 
 #[repr(C, packed(4))]
 #[derive(Copy, Clone, Debug)]
 pub struct PageWeather {
     pub header: PageHeader,
+
+    /// size of the track nodes the rain grid is spread over (meters)
+    pub track_node_size: f64,
+    pub weather_info: PageWeatherControlInfo,
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageWeatherControlInfo {
+    /// target time for the values below (session time)
+    pub et: f64,
+
+    /// rain (0.0-1.0) at 9 nodes, a 3x3 grid centered on the track (the middle one is index 4)
+    pub raining: [f64; 9],
+
+    /// cloudiness (0.0-1.0)
+    pub cloudiness: f64,
+    /// temperature (Kelvin)
+    pub ambient_temp_k: f64,
+    /// maximum speed of the wind (ground speed, but it affects how fast the clouds move, too)
+    pub wind_max_speed: f64,
+
+    /// whether the cloudiness should be applied instantly or gradually
+    pub apply_cloudiness_instantly: u8,
+    unused: [Garbage; 3],
+
+    /// for future use
+    expansion: [Garbage; 508],
 }
 
 #[repr(C, packed(4))]
@@ -891,3 +1226,356 @@ pub struct PageVehScoringCapture {
     pub is_player: u8,
     pub finish_status: i8,
 }
+
+// Input buffers
+// These are written by us and read by the rF2SharedMemoryMap plugin
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageInputHeader {
+    /// Incremented right before buffer is written to.
+    pub version_update_begin: u32,
+    /// Incremented after buffer write is done.
+    pub version_update_end: u32,
+    /// Layout version of the buffer, the plugin ignores writes with a different version
+    pub layout_version: i32,
+}
+
+/// Layout version of PageHWControl
+pub const HW_CONTROL_LAYOUT_VERSION: i32 = 1;
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageHWControl {
+    pub header: PageInputHeader,
+
+    /// name of the control, as used by InternalsPlugin::CheckHWControl
+    pub control_name: String96,
+    /// value to return, 1.0 for pressed
+    pub ret_val: f64,
+}
+
+/// Layout version of PagePluginControl
+pub const PLUGIN_CONTROL_LAYOUT_VERSION: i32 = 1;
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PagePluginControl {
+    pub header: PageInputHeader,
+
+    /// RF2SubscribedBuffer flags of the buffers to enable on top of the configured ones
+    pub request_enable_buffers_mask: i32,
+    /// request the HWControl input buffer to be enabled
+    pub request_hw_control_input: u8,
+    /// request the WeatherControl input buffer to be enabled
+    pub request_weather_control_input: u8,
+    /// request the RulesControl input buffer to be enabled
+    pub request_rules_control_input: u8,
+}
+
+/// Flags for the buffers of the rF2SharedMemoryMap plugin, as used in the
+/// UnsubscribedBuffersMask and PluginControl
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RF2SubscribedBuffer {
+    Telemetry = 1,
+    Scoring = 2,
+    Rules = 4,
+    MultiRules = 8,
+    ForceFeedback = 16,
+    Graphics = 32,
+    PitInfo = 64,
+    Weather = 128,
+}
+
+// Layout checks against the structs in rF2State.h (packed to 4 bytes),
+// share::connect passes these sizes to the bridge, so a transcription error would map garbage.
+// Each page has the size and the offsets of the fields we navigate by checked
+macro_rules! assert_layout {
+    ($page:ty, $size:expr $(, $field:ident = $offset:expr)*) => {
+        const _: () = assert!(std::mem::size_of::<$page>() == $size, concat!("Size of ", stringify!($page), " differs from rF2State.h"));
+        $(const _: () = assert!(std::mem::offset_of!($page, $field) == $offset, concat!("Offset of ", stringify!($page), "::", stringify!($field), " differs from rF2State.h"));)*
+    };
+}
+
+assert_layout!(PageHeader, 8, version_update_begin = 0, version_update_end = 4);
+assert_layout!(PageVec3, 24);
+
+assert_layout!(PageTelemetry, 241680, header = 0, bytes_updated_hint = 8, num_vehicles = 12, vehicles = 16);
+assert_layout!(PageVehicleTelemetry, 1888, id = 0, vehicle_name = 32, pos = 160, gear = 352, fuel = 524, engine_torque = 592,
+    rear_flap_legal_status = 618, ignition_starter = 619, front_tire_compound_name = 620, wheels = 848);
+assert_layout!(PageWheelTelemetry, 260, temperature = 128, terrain_name = 160, tire_inner_layer_temperature = 212);
+
+assert_layout!(PageScoring, 75312, header = 0, bytes_updated_hint = 8, scoring_info = 12, vehicles = 560);
+assert_layout!(PageScoringInfo, 548, session = 64, num_vehicles = 104, game_phase = 108, player_name = 116, server_name = 296);
+assert_layout!(PageVehicleScoring, 584, id = 0, driver_name = 4, place = 199, vehicle_class = 200, pit_state = 457, flag = 504);
+
+assert_layout!(PageRules, 45272, header = 0, bytes_updated_hint = 8, track_rules = 12, actions = 728, participants = 2776);
+assert_layout!(PageMultiRules, 40816, header = 0, bytes_updated_hint = 8, multi_session_rules = 12, participants = 368);
+assert_layout!(PageMultiSessionRules, 356, session = 0, track_type = 8, num_participants = 40, num_qual_sessions = 52, max_laps = 60, name = 68);
+assert_layout!(PageMultiSessionParticipant, 316, id = 0, driver_name = 4, vehicle_name = 36, qualification_time = 124,
+    server_scored = 172, grid_position = 176, garage_index = 184);
+
+assert_layout!(PageForceFeedback, 16, force_value = 8);
+assert_layout!(PageGraphics, 272, header = 0, graphics_info = 8);
+assert_layout!(PagePitInfo, 340, header = 0, pit_menu = 8);
+assert_layout!(PageWeather, 632, header = 0, track_node_size = 8, weather_info = 16);
+assert_layout!(PageExtended, 10144, header = 0, version = 8, is64bit = 20, physics = 24, tracked_damages = 64,
+    session_transition_capture = 8276, status_message = 9452, lsi_rules_instruction_message = 10048);
+
+assert_layout!(PageInputHeader, 12, version_update_begin = 0, version_update_end = 4, layout_version = 8);
+assert_layout!(PageHWControl, 116, header = 0, control_name = 12, ret_val = 108);
+assert_layout!(PagePluginControl, 20, header = 0, request_enable_buffers_mask = 12, request_hw_control_input = 16,
+    request_weather_control_input = 17, request_rules_control_input = 18);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enums_decode_every_byte() {
+        for value in 0..=u8::MAX {
+            match CharBool::from_raw(value) {
+                Some(decoded) => assert_eq!(decoded as u8, value),
+                None => assert!(value > 2, "CharBool {value}"),
+            }
+            match RF2RearFlapLegalStatus::from_raw(value) {
+                Some(decoded) => assert_eq!(decoded as u8, value),
+                None => assert!(value > 2, "RF2RearFlapLegalStatus {value}"),
+            }
+            match RF2IgnitionStarterStatus::from_raw(value) {
+                Some(decoded) => assert_eq!(decoded as u8, value),
+                None => assert!(value > 2, "RF2IgnitionStarterStatus {value}"),
+            }
+            match RF2GamePhase::from_raw(value) {
+                Some(decoded) => assert_eq!(decoded as u8, value),
+                None => assert!(value > 9, "RF2GamePhase {value}"),
+            }
+
+            let value = value as i8;
+            match RF2YellowFlagState::from_raw(value) {
+                Some(decoded) => assert_eq!(decoded as i8, value),
+                None => assert!(!(-1..=7).contains(&value), "RF2YellowFlagState {value}"),
+            }
+        }
+    }
+
+    /// Page bytes as the plugin would write them, values placed at the offsets from rF2State.h
+    struct Fixture(Vec<u8>);
+
+    impl Fixture {
+        fn new<T>() -> Self {
+            Fixture(vec![0; std::mem::size_of::<T>()])
+        }
+
+        fn put(&mut self, offset: usize, bytes: &[u8]) -> &mut Self {
+            self.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+            self
+        }
+
+        /// Reads the bytes back through the struct, like SharedMemory does with the map
+        fn read<T: Copy>(&self) -> T {
+            assert_eq!(self.0.len(), std::mem::size_of::<T>());
+            unsafe { std::ptr::read_unaligned(self.0.as_ptr() as *const T) }
+        }
+    }
+
+    fn string<const N: usize>(bytes: &[u8; N]) -> &str {
+        let end = bytes.iter().position(|c| *c == 0).unwrap_or(N);
+        std::str::from_utf8(&bytes[..end]).unwrap()
+    }
+
+    #[test]
+    fn telemetry_fixture() {
+        const VEHICLE: usize = 16 + 1888;
+        const WHEEL: usize = VEHICLE + 848 + 2 * 260;
+
+        let mut fixture = Fixture::new::<PageTelemetry>();
+        fixture.put(0, &7u32.to_le_bytes()).put(4, &7u32.to_le_bytes())
+            .put(8, &1234i32.to_le_bytes())
+            .put(12, &2i32.to_le_bytes())
+            .put(VEHICLE, &42i32.to_le_bytes())
+            .put(VEHICLE + 32, b"Oreca 07")
+            .put(VEHICLE + 160 + 8, &(-12.5f64).to_le_bytes())
+            .put(VEHICLE + 352, &(-1i32).to_le_bytes())
+            .put(VEHICLE + 524, &55.25f64.to_le_bytes())
+            .put(VEHICLE + 592, &310.0f64.to_le_bytes())
+            .put(VEHICLE + 618, &[2, 1])
+            .put(VEHICLE + 620, b"Medium")
+            .put(WHEEL + 128 + 8, &350.5f64.to_le_bytes())
+            .put(WHEEL + 160, b"RACELINE")
+            .put(WHEEL + 212 + 16, &361.0f64.to_le_bytes());
+
+        let page = fixture.read::<PageTelemetry>();
+        assert_eq!(({ page.header.version_update_begin }, { page.header.version_update_end }), (7, 7));
+        assert_eq!({ page.bytes_updated_hint }, 1234);
+        assert_eq!({ page.num_vehicles }, 2);
+
+        let vehicle = page.vehicles[1];
+        assert_eq!({ vehicle.id }, 42);
+        assert_eq!(string(&{ vehicle.vehicle_name }), "Oreca 07");
+        assert_eq!({ vehicle.pos.y }, -12.5);
+        assert_eq!({ vehicle.gear }, -1);
+        assert_eq!({ vehicle.fuel }, 55.25);
+        assert_eq!({ vehicle.engine_torque }, 310.0);
+        assert_eq!(vehicle.rear_flap_legal_status(), Some(RF2RearFlapLegalStatus::Allowed));
+        assert_eq!(vehicle.ignition_starter(), Some(RF2IgnitionStarterStatus::Ignition));
+        assert_eq!(string(&{ vehicle.front_tire_compound_name }), "Medium");
+
+        let wheel = vehicle.wheels[2];
+        assert_eq!({ wheel.temperature }[1], 350.5);
+        assert_eq!(string(&{ wheel.terrain_name }), "RACELINE");
+        assert_eq!({ wheel.tire_inner_layer_temperature }[2], 361.0);
+
+        // Nothing spilled into the neighbours
+        assert_eq!({ page.vehicles[0].id }, 0);
+        assert_eq!({ page.vehicles[2].id }, 0);
+        assert_eq!({ vehicle.wheels[1].temperature }, [0.0; 3]);
+    }
+
+    #[test]
+    fn scoring_fixture() {
+        const VEHICLE: usize = 560 + 3 * 584;
+
+        let mut fixture = Fixture::new::<PageScoring>();
+        fixture.put(8, &99i32.to_le_bytes())
+            .put(12 + 64, &11i32.to_le_bytes())
+            .put(12 + 104, &4i32.to_le_bytes())
+            .put(12 + 108, &[5])
+            .put(12 + 116, b"Player One")
+            .put(12 + 296, b"Server")
+            .put(VEHICLE, &17i32.to_le_bytes())
+            .put(VEHICLE + 4, b"Driver Four")
+            .put(VEHICLE + 199, &[3])
+            .put(VEHICLE + 200, b"Hypercar")
+            .put(VEHICLE + 457, &[2])
+            .put(VEHICLE + 504, &[6]);
+
+        let page = fixture.read::<PageScoring>();
+        assert_eq!({ page.bytes_updated_hint }, 99);
+
+        let info = page.scoring_info;
+        assert_eq!(RF2SessionType::from_raw(info.session), Some(RF2SessionType::Race(2)));
+        assert_eq!({ info.num_vehicles }, 4);
+        assert_eq!(RF2GamePhase::from_raw(info.game_phase), Some(RF2GamePhase::GreenFlag));
+        assert_eq!(string(&{ info.player_name }), "Player One");
+        assert_eq!(string(&{ info.server_name }), "Server");
+
+        let vehicle = page.vehicles[3];
+        assert_eq!({ vehicle.id }, 17);
+        assert_eq!(string(&{ vehicle.driver_name }), "Driver Four");
+        assert_eq!({ vehicle.place }, 3);
+        assert_eq!(string(&{ vehicle.vehicle_class }), "Hypercar");
+        assert_eq!({ vehicle.pit_state }, 2);
+        assert_eq!({ vehicle.flag }, 6);
+        assert_eq!({ page.vehicles[2].id }, 0);
+    }
+
+    #[test]
+    fn multi_rules_fixture() {
+        const RULES: usize = 12;
+        const PARTICIPANT: usize = 368 + 2 * 316;
+
+        let mut fixture = Fixture::new::<PageMultiRules>();
+        fixture.put(RULES, &10i32.to_le_bytes())
+            .put(RULES + 8, b"Permanent")
+            .put(RULES + 40, &3i32.to_le_bytes())
+            .put(RULES + 52, &2i32.to_le_bytes())
+            .put(RULES + 56, &1i32.to_le_bytes())
+            .put(RULES + 60, &30i32.to_le_bytes())
+            .put(RULES + 68, b"Feature Race")
+            .put(PARTICIPANT, &8i32.to_le_bytes())
+            .put(PARTICIPANT + 4, b"Driver Three")
+            .put(PARTICIPANT + 36, b"Oreca 07")
+            .put(PARTICIPANT + 124 + 4, &95.5f32.to_le_bytes())
+            .put(PARTICIPANT + 172, &[1])
+            .put(PARTICIPANT + 176, &5i32.to_le_bytes())
+            .put(PARTICIPANT + 184, &7i32.to_le_bytes());
+
+        let page = fixture.read::<PageMultiRules>();
+        let rules = page.multi_session_rules;
+        assert_eq!(RF2SessionType::from_raw(rules.session), Some(RF2SessionType::Race(1)));
+        assert_eq!(string(&{ rules.track_type }), "Permanent");
+        assert_eq!({ rules.num_participants }, 3);
+        assert_eq!({ rules.num_qual_sessions }, 2);
+        assert_eq!({ rules.num_race_sessions }, 1);
+        assert_eq!({ rules.max_laps }, 30);
+        assert_eq!(string(&{ rules.name }), "Feature Race");
+
+        let participant = page.participants[2];
+        assert_eq!({ participant.id }, 8);
+        assert_eq!(string(&{ participant.driver_name }), "Driver Three");
+        assert_eq!(string(&{ participant.vehicle_name }), "Oreca 07");
+        assert_eq!({ participant.qualification_time }[1], 95.5);
+        assert_eq!({ participant.server_scored }, 1);
+        assert_eq!({ participant.grid_position }, 5);
+        assert_eq!({ participant.garage_index }, 7);
+        assert_eq!({ page.participants[1].id }, 0);
+        assert_eq!({ page.participants[3].id }, 0);
+    }
+
+    #[test]
+    fn small_page_fixtures() {
+        let page = Fixture::new::<PageForceFeedback>().put(8, &(-0.75f64).to_le_bytes()).read::<PageForceFeedback>();
+        assert_eq!({ page.force_value }, -0.75);
+
+        let page = Fixture::new::<PageWeather>()
+            .put(8, &3.0f64.to_le_bytes())
+            .put(16, &600.0f64.to_le_bytes())
+            .put(16 + 8 + 4 * 8, &0.5f64.to_le_bytes())
+            .read::<PageWeather>();
+        assert_eq!({ page.track_node_size }, 3.0);
+        assert_eq!({ page.weather_info.et }, 600.0);
+        assert_eq!({ page.weather_info.raining }[4], 0.5);
+
+        let page = Fixture::new::<PageExtended>()
+            .put(8, b"3.7.15.1")
+            .put(20, &[1])
+            .put(9452, b"Status")
+            .put(10048, b"Let the leader pass")
+            .read::<PageExtended>();
+        assert_eq!(string(&{ page.version }), "3.7.15.1");
+        assert_eq!({ page.is64bit }, 1);
+        assert_eq!(string(&{ page.status_message }), "Status");
+        assert_eq!(string(&{ page.lsi_rules_instruction_message }), "Let the leader pass");
+
+        let page = Fixture::new::<PageHWControl>()
+            .put(8, &HW_CONTROL_LAYOUT_VERSION.to_le_bytes())
+            .put(12, b"PitRequest")
+            .put(108, &1.0f64.to_le_bytes())
+            .read::<PageHWControl>();
+        assert_eq!({ page.header.layout_version }, HW_CONTROL_LAYOUT_VERSION);
+        assert_eq!(string(&{ page.control_name }), "PitRequest");
+        assert_eq!({ page.ret_val }, 1.0);
+
+        let page = Fixture::new::<PagePluginControl>()
+            .put(12, &(RF2SubscribedBuffer::Weather as i32).to_le_bytes())
+            .put(16, &[1, 1, 0])
+            .read::<PagePluginControl>();
+        assert_eq!({ page.request_enable_buffers_mask }, 128);
+        assert_eq!({ page.request_hw_control_input }, 1);
+        assert_eq!({ page.request_weather_control_input }, 1);
+        assert_eq!({ page.request_rules_control_input }, 0);
+    }
+
+    #[test]
+    fn session_type_decodes_every_byte() {
+        for value in (i8::MIN as i32)..=(u8::MAX as i32) {
+            let Some(session) = RF2SessionType::from_raw(value) else {
+                assert!(!(0..=13).contains(&value), "RF2SessionType {value}");
+                continue;
+            };
+
+            let expected = match value {
+                0 => ("TestDay", 1),
+                1..=4 => ("Practice", value),
+                5..=8 => ("Qualifying", value - 4),
+                9 => ("Warmup", 1),
+                _ => ("Race", value - 9),
+            };
+            assert_eq!((session.name(), session.number() as i32), expected, "RF2SessionType {value}");
+        }
+        assert_eq!(RF2SessionType::from_raw(i32::MAX), None);
+        assert_eq!(RF2SessionType::from_raw(i32::MIN), None);
+    }
+}
@@ -19,8 +19,14 @@ mod share;
 pub(crate) mod data;
 /// Contains the propertyhandles and does the writing to them
 mod reader;
+/// User configuration
+mod config;
+/// Sends DataRace actions as control inputs to the game
+mod control;
+/// Paces the update runner to the page update rates
+mod scheduler;
 
-pub use share::{MapHolder, SharedMemory};
+pub use share::{InputBuffer, MapHolder, SharedMemory, VehicleRead};
 
 pub(crate) struct State {
     // Used to lock the update thread
@@ -32,20 +38,36 @@ pub(crate) struct State {
     // 100 updater offline
     // 101 updater offline and locked
     update_lock: AtomicU32,
+
+    config: config::Config,
+    /// Filled by actions, sent to the game by the updater
+    controls: control::ControlQueue,
+    /// Unsubscribed pages in use, the updater requests them from the game
+    buffer_uses: control::BufferUses,
+    /// Results of the setup checks on init, the updater checks the running game again before connecting
+    preflight: share::Preflight,
 }
 
 #[datarace_plugin_api::macros::plugin_init]
 fn handle_init(handle: PluginHandle) -> Result<(),String> {
     // reader::init_properties(&handle)?; // Testing
 
+    let config = config::Config::from_env(&handle);
+
     // Installation of memory map bridge (on linux) and plugin
-    share::init_setup(&handle)?;
+    let preflight = share::init_setup(&handle, &config);
 
     // Property creation
-    reader::init_properties(&handle)?;
+    reader::init_properties(&handle, &preflight)?;
 
     // State
-    let state = State { update_lock: AtomicU32::new(100) };
+    let state = State {
+        update_lock: AtomicU32::new(100),
+        buffer_uses: control::initial_buffer_uses(&config),
+        config,
+        controls: control::ControlQueue::default(),
+        preflight,
+    };
     unsafe { save_state_now!(handle, state) };
 
 
@@ -62,6 +84,10 @@ fn handle_update(handle: PluginHandle, msg: Message) -> Result<(), String> {
 
     match msg {
         Message::StartupFinished => {
+            if !state.preflight.all_ok() {
+                // Not blocking, the updater checks the running game again, and connects if it has the essentials
+                handle.log_error("Setup incomplete (see rf2-reader.health)");
+            }
             handle.log_info("Startup completed, starting background worker thread");
             std::thread::spawn(|| updater(handle));
         },
@@ -128,6 +154,9 @@ fn handle_update(handle: PluginHandle, msg: Message) -> Result<(), String> {
                 _ => ()
             }
         },
+        Message::ActionRecv(action) => {
+            control::handle_action(&handle, &action, &state.controls, &state.buffer_uses);
+        },
         Message::OtherPluginStarted(_) => (),
         _ => {
             handle.log_error("Unkown Message received (update this plugin)");
@@ -142,36 +171,39 @@ fn updater(handle: PluginHandle) {
     let sta = get_state!(handle).expect("Gimme!");
 
 
-    let mut runchecker_helper_state = if let Some(res) = share::GameRunningHelperState::new(&handle) {
-        res
-    } else {
-        handle.log_error("Updater aborting due to being unable to aquire necessary resources!");
-        handle.send_internal_msg(-1);
-        // TODO implement a way to shutdown the plugin
-        return;
-    };
-
-
+    let mut runchecker_helper_state = share::GameRunningHelperState::new();
+    let mut preflight = sta.preflight.clone();
 
     // Outer game check running loop
     loop {
 
         if share::check_if_game_running(&mut runchecker_helper_state) {
-            handle.log_info("Game is detected running, starting updater...");
-
-            match share::connect(&handle, &mut runchecker_helper_state) {
-                Ok(mount) => {
-                    let exit = !runner_loop(sta, &handle, &mount, &mut runchecker_helper_state);
-                    handle.log_info("Exiting Updater...");
-                    share::disconnect(&handle, &mut runchecker_helper_state, Some(mount));
-
-                    if exit {
-                        return;
+            let name = runchecker_helper_state.game().map(|game| game.name).unwrap_or("Game");
+            handle.log_info(format!("{name} is detected running, starting updater..."));
+
+            // Things might have been installed since the last attempt
+            let ready = share::check_setup(&handle, &sta.config, &mut runchecker_helper_state, &mut preflight);
+            reader::update_health(&handle, &preflight);
+
+            if !ready {
+                handle.log_error(format!("{name} setup incomplete (see rf2-reader.health), retrying"));
+            } else {
+                match share::connect(&handle, &mut runchecker_helper_state) {
+                    Ok(mut mount) => {
+                        reader::set_game(&handle, runchecker_helper_state.game());
+                        let exit = !runner_loop(sta, &handle, &mut mount, &mut runchecker_helper_state);
+                        handle.log_info("Exiting Updater...");
+                        reader::set_game(&handle, None);
+                        share::disconnect(&handle, &mut runchecker_helper_state, Some(mount));
+
+                        if exit {
+                            return;
+                        }
+                    },
+                    Err(e) => {
+                        share::disconnect(&handle, &mut runchecker_helper_state, None);
+                        handle.log_error(format!("Updater failed to mount memory maps (Retrying): {e}"));
                     }
-                },
-                Err(e) => {
-                    share::disconnect(&handle, &mut runchecker_helper_state, None);
-                    handle.log_error(format!("Updater failed to mount memory maps (Retrying): {e}"));
                 }
             }
         }
@@ -181,9 +213,13 @@ fn updater(handle: PluginHandle) {
     }
 }
 
+/// How often we try to mount requested pages that are not available yet
+const REMOUNT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+const REMOUNT_ATTEMPTS: u32 = 6;
+
 /// Contains to active update runner and it's locking mechanism
 /// return value indicates if programm should exit (false), or continue (true)
-fn runner_loop(sta: &PluginState, handle: &PluginHandle, mount: &MapHolder, runchecker_helper_state: &mut share::GameRunningHelperState) -> bool {
+fn runner_loop(sta: &PluginState, handle: &PluginHandle, mount: &mut MapHolder, runchecker_helper_state: &mut share::GameRunningHelperState) -> bool {
     // Game running, starting up loop
     // Checking for startup handle lock
     while let Err(v) = sta.update_lock.compare_exchange(100, 0, Ordering::AcqRel, Ordering::Acquire) {
@@ -195,7 +231,15 @@ fn runner_loop(sta: &PluginState, handle: &PluginHandle, mount: &MapHolder, runc
     }
     handle.log_info("Updater Started");
 
-    let mut reader_state = reader::ReaderState::default();
+    let mut reader_state = reader::ReaderState::new(&sta.config);
+    let mut control_writer = control::ControlWriter::new(&sta.controls);
+    let mut scheduler = scheduler::Scheduler::new();
+
+    // Pages take the bridge (or the plugin when requested) a moment to create, so we retry mounting missing pages for a bit
+    let mut remount_attempts = REMOUNT_ATTEMPTS;
+    let mut next_remount = std::time::Instant::now();
+    // Buffers requested so far, None as we always send a request (for the HWControl input)
+    let mut requested_buffers = None;
 
     loop {
         match sta.update_lock.load(Ordering::Acquire) {
@@ -226,6 +270,30 @@ fn runner_loop(sta: &PluginState, handle: &PluginHandle, mount: &MapHolder, runc
                 handle.log_error(format!("Reading update failed: {e}"));
             }
         }
+
+        let buffer_uses = sta.buffer_uses.load(Ordering::Acquire);
+        reader_state.set_ffb_in_use(buffer_uses & data::RF2SubscribedBuffer::ForceFeedback as i32 != 0);
+        if requested_buffers != Some(buffer_uses) {
+            if share::request_buffers(handle, mount, buffer_uses) {
+                remount_attempts = REMOUNT_ATTEMPTS;
+            }
+            requested_buffers = Some(buffer_uses);
+        }
+
+        if remount_attempts > 0 && next_remount <= std::time::Instant::now() {
+            let had_plugin_control = mount.plugin_control.is_some();
+            remount_attempts = if mount.mount_missing(handle) { 0 } else { remount_attempts - 1 };
+            next_remount = std::time::Instant::now() + REMOUNT_INTERVAL;
+
+            // Requests made before PluginControl showed up went nowhere
+            if !had_plugin_control && mount.plugin_control.is_some() {
+                requested_buffers = None;
+            }
+        }
+
+        control_writer.process(handle, mount.hw_control.as_ref(), &sta.controls);
+
+        scheduler.pace(&reader_state);
     }
 
     // handle.log_info("Hewo!");
@@ -1,14 +1,41 @@
-use std::usize;
-
-use datarace_plugin_api::{macros::generate_property_handle, wrappers::{DataStoreReturnCode, PluginHandle, Property, PropertyHandle}};
-
-use crate::{data::{PageScoring, PageVehicleTelemetry, RF2IgnitionStarterStatus, RF2RearFlapLegalStatus, MAX_MAPPED_VEHICLES}, share::{self, check_if_game_running}, MapHolder};
+use std::{sync::atomic::{AtomicU64, Ordering}, usize};
+
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{DataStoreReturnCode, EventHandle, PluginHandle, Property, PropertyHandle}};
+
+/// Sorted leaderboard of the whole field
+mod standings;
+/// General session info from scoring
+mod session;
+/// Current weather and track conditions from scoring, the weather target from the Weather page
+mod weather;
+/// Extended page, physics options and plugin info
+mod extended;
+/// Player damage, and impacts of the whole field
+mod damage;
+/// 400Hz ForceFeedback value and clipping statistics
+mod ffb;
+/// Current pit menu selection from the PitInfo page
+mod pit_menu;
+/// Session transition info and the starting grid from the MultiRules page
+mod grid;
+/// Results of the setup checks
+mod health;
+
+use crate::{config::Config, data::{PageRules, PageScoring, PageVehicleTelemetry, PageWheelTelemetry, RF2IgnitionStarterStatus, RF2RearFlapLegalStatus, MAX_MAPPED_VEHICLES}, scheduler::PageVersions, share::{self, check_if_game_running, VehicleRead}, MapHolder};
 
 const P_EXTRA: PropertyHandle = generate_property_handle!("rF2-Reader.extra");
+/// Name of the game running, empty if none
+const P_GAME: PropertyHandle = generate_property_handle!("rf2-reader.game");
 
 // Telemetry
 const P_TELEMETRY_UPDATE: PropertyHandle = generate_property_handle!("rF2-Reader.telemetry.update");
 const P_DEBUG_TELEMETRY_TIME: PropertyHandle = generate_property_handle!("rf2-reader.debug.telemetry.time");
+/// Enum values outside the known variants, in any page
+const P_DEBUG_INVALID_VALUES: PropertyHandle = generate_property_handle!("rf2-reader.debug.invalid_values");
+/// Frames that read torn at first, but succeeded on a retry
+const P_DEBUG_READ_RETRIED: PropertyHandle = generate_property_handle!("rf2-reader.debug.read.retried");
+/// Frames dropped as every retry was torn
+const P_DEBUG_READ_TORN: PropertyHandle = generate_property_handle!("rf2-reader.debug.read.torn");
 
 // Left overs:
 // /// slot ID (note that it can be re-used in multiplayer after someone leaves)    
@@ -29,16 +56,6 @@ const P_DEBUG_TELEMETRY_TIME: PropertyHandle = generate_property_handle!("rf2-re
 // /// rotational acceleration (radians/sec^2) in local vehicle coordinates    
 // pub local_rot_accel: PageVec3,
 //
-// /// whether any parts (besides wheels) have been detached    
-// pub detached: u8,
-//     /// dent severity at 8 locations around the car (0=none, 1=some, 2=more)    
-//     pub dent_severity: [u8; 8],
-//     /// time of last impact    
-//     pub last_impact_et: f64,
-//     /// magnitude of last impact    
-//     pub last_impact_magnitude: f64,
-//     /// location of last impact    
-//     pub last_impact_pos: PageVec3,
 //     /// offset from static CG to graphical center    
 //     pub physics_to_graphics_offset: [f32; 3],
 //
@@ -113,94 +130,170 @@ const P_TELEMETRY_REAR_BRAKE_BIAS: PropertyHandle = generate_property_handle!("r
 const P_TELEMETRY_ENGINE_TURBO_BOOST_PRESSURE: PropertyHandle = generate_property_handle!("rf2-reader.telemetry.engine.turbo_boost_pressure");
 const P_TELEMETRY_PHYSICAL_WHEEL_RANGE: PropertyHandle = generate_property_handle!("rf2-reader.telemetry.physical_steering_wheel_range");
 
-// TODO wheels
+// Wheels
+/// Order of the wheels in the telemetry
+const WHEEL_NAMES: [&'static str; 4] = ["fl", "fr", "rl", "rr"];
+
+/// As the handles have to be generated at compile time we have one set of them per wheel
+struct WheelProperties {
+    pressure: PropertyHandle,
+    temp_left: PropertyHandle,
+    temp_center: PropertyHandle,
+    temp_right: PropertyHandle,
+    carcass_temp: PropertyHandle,
+    inner_temp_left: PropertyHandle,
+    inner_temp_center: PropertyHandle,
+    inner_temp_right: PropertyHandle,
+    wear: PropertyHandle,
+    brake_temp: PropertyHandle,
+    brake_pressure: PropertyHandle,
+    suspension_deflection: PropertyHandle,
+    ride_height: PropertyHandle,
+    camber: PropertyHandle,
+    toe: PropertyHandle,
+    grip_fraction: PropertyHandle,
+    tire_load: PropertyHandle,
+    flat: PropertyHandle,
+    detached: PropertyHandle,
+    terrain_name: PropertyHandle,
+}
 
-//
-//     // keeping this at the end of the structure to make it easier to replace in future versions
-//     /// wheel info (front left, front right, rear left, rear right)    
-//     pub wheels: [PageWheelTelemetry; 4],
-// }
-//
-// #[repr(C, packed(4))]
-// #[derive(Copy, Clone, Debug)]
-// pub struct PageWheelTelemetry {
-//     /// meters
-//     pub suspension_deflection: f64,
-//     /// meters
-//     pub ride_height: f64,
-//     /// pushrod load in Newtons
-//     pub susp_force: f64,
-//     /// Celsius
-//     pub brake_temp: f64,
-//     /// currently 0.0-1.0, depending on driver input and brake balance; will convert to true brake pressure (kPa) in future
-//     pub brake_pressure: f64,
-//
-//     /// radians/sec
-//     pub rotation: f64,
-//     /// lateral velocity at contact patch
-//     pub lateral_patch_vel: f64,
-//     /// longitudinal velocity at contact patch
-//     pub longitudinal_patch_vel: f64,
-//     /// lateral velocity at contact patch
-//     pub lateral_ground_vel: f64,
-//     /// longitudinal velocity at contact patch
-//     pub longitudinal_ground_vel: f64,
-//     /// radians (positive is left for left-side wheels, right for right-side wheels)
-//     pub camber: f64,
-//     /// Newtons
-//     pub lateral_force: f64,
-//     /// Newtons
-//     pub longitudinal_force: f64,
-//     /// Newtons
-//     pub tire_load: f64,
-//
-//     /// an approximation of what fraction of the contact patch is sliding
-//     pub grip_fract: f64,
-//     /// kPa (tire pressure)
-//     pub pressure: f64,
-//     /// Kelvin (subtract 273.15 to get Celsius), left/center/right (not to be confused with inside/center/outside!)
-//     pub temperature: [f64; 3],
-//     /// wear (0.0-1.0, fraction of maximum) ... this is not necessarily proportional with grip loss
-//     pub wear: f64,
-//     /// the material prefixes from the TDF file
-//     pub terrain_name: String16,
-//     /// Enum for surface type
-//     pub surface_type: u8,
-//     /// whether tire is flat
-//     pub flat: u8,
-//     /// whether wheel is detached
-//     pub detached: u8,
-//     /// tire radius in centimeters
-//     pub static_undeflected_radius: u8,
-//
-//     /// how much is tire deflected from its (speed-sensitive) radius
-//     pub vertical_tire_deflection: f64,
-//     /// wheel's y location relative to vehicle y location
-//     pub wheel_ylocation: f64,
-//     /// current toe angle w.r.t. the vehicle
-//     pub toe: f64,
-//
-//     /// rough average of temperature samples from carcass (Kelvin)
-//     pub tire_carcass_temperature: f64,
-//     /// rough average of temperature samples from innermost layer of rubber (before carcass) (Kelvin)
-//     pub tire_inner_layer_temperature: [f64; 3],
-//
-//     /// for future use
-//     expansion: [Garbage; 24],
-// }
-//
+const P_TELEMETRY_WHEELS: [WheelProperties; 4] = [
+    WheelProperties {
+        pressure: generate_property_handle!("rf2-reader.telemetry.wheel.fl.pressure"),
+        temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.fl.temp_left"),
+        temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.fl.temp_center"),
+        temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.fl.temp_right"),
+        carcass_temp: generate_property_handle!("rf2-reader.telemetry.wheel.fl.carcass_temp"),
+        inner_temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.fl.inner_temp_left"),
+        inner_temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.fl.inner_temp_center"),
+        inner_temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.fl.inner_temp_right"),
+        wear: generate_property_handle!("rf2-reader.telemetry.wheel.fl.wear"),
+        brake_temp: generate_property_handle!("rf2-reader.telemetry.wheel.fl.brake_temp"),
+        brake_pressure: generate_property_handle!("rf2-reader.telemetry.wheel.fl.brake_pressure"),
+        suspension_deflection: generate_property_handle!("rf2-reader.telemetry.wheel.fl.suspension_deflection"),
+        ride_height: generate_property_handle!("rf2-reader.telemetry.wheel.fl.ride_height"),
+        camber: generate_property_handle!("rf2-reader.telemetry.wheel.fl.camber"),
+        toe: generate_property_handle!("rf2-reader.telemetry.wheel.fl.toe"),
+        grip_fraction: generate_property_handle!("rf2-reader.telemetry.wheel.fl.grip_fraction"),
+        tire_load: generate_property_handle!("rf2-reader.telemetry.wheel.fl.tire_load"),
+        flat: generate_property_handle!("rf2-reader.telemetry.wheel.fl.flat"),
+        detached: generate_property_handle!("rf2-reader.telemetry.wheel.fl.detached"),
+        terrain_name: generate_property_handle!("rf2-reader.telemetry.wheel.fl.terrain_name"),
+    },
+    WheelProperties {
+        pressure: generate_property_handle!("rf2-reader.telemetry.wheel.fr.pressure"),
+        temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.fr.temp_left"),
+        temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.fr.temp_center"),
+        temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.fr.temp_right"),
+        carcass_temp: generate_property_handle!("rf2-reader.telemetry.wheel.fr.carcass_temp"),
+        inner_temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.fr.inner_temp_left"),
+        inner_temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.fr.inner_temp_center"),
+        inner_temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.fr.inner_temp_right"),
+        wear: generate_property_handle!("rf2-reader.telemetry.wheel.fr.wear"),
+        brake_temp: generate_property_handle!("rf2-reader.telemetry.wheel.fr.brake_temp"),
+        brake_pressure: generate_property_handle!("rf2-reader.telemetry.wheel.fr.brake_pressure"),
+        suspension_deflection: generate_property_handle!("rf2-reader.telemetry.wheel.fr.suspension_deflection"),
+        ride_height: generate_property_handle!("rf2-reader.telemetry.wheel.fr.ride_height"),
+        camber: generate_property_handle!("rf2-reader.telemetry.wheel.fr.camber"),
+        toe: generate_property_handle!("rf2-reader.telemetry.wheel.fr.toe"),
+        grip_fraction: generate_property_handle!("rf2-reader.telemetry.wheel.fr.grip_fraction"),
+        tire_load: generate_property_handle!("rf2-reader.telemetry.wheel.fr.tire_load"),
+        flat: generate_property_handle!("rf2-reader.telemetry.wheel.fr.flat"),
+        detached: generate_property_handle!("rf2-reader.telemetry.wheel.fr.detached"),
+        terrain_name: generate_property_handle!("rf2-reader.telemetry.wheel.fr.terrain_name"),
+    },
+    WheelProperties {
+        pressure: generate_property_handle!("rf2-reader.telemetry.wheel.rl.pressure"),
+        temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.rl.temp_left"),
+        temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.rl.temp_center"),
+        temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.rl.temp_right"),
+        carcass_temp: generate_property_handle!("rf2-reader.telemetry.wheel.rl.carcass_temp"),
+        inner_temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.rl.inner_temp_left"),
+        inner_temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.rl.inner_temp_center"),
+        inner_temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.rl.inner_temp_right"),
+        wear: generate_property_handle!("rf2-reader.telemetry.wheel.rl.wear"),
+        brake_temp: generate_property_handle!("rf2-reader.telemetry.wheel.rl.brake_temp"),
+        brake_pressure: generate_property_handle!("rf2-reader.telemetry.wheel.rl.brake_pressure"),
+        suspension_deflection: generate_property_handle!("rf2-reader.telemetry.wheel.rl.suspension_deflection"),
+        ride_height: generate_property_handle!("rf2-reader.telemetry.wheel.rl.ride_height"),
+        camber: generate_property_handle!("rf2-reader.telemetry.wheel.rl.camber"),
+        toe: generate_property_handle!("rf2-reader.telemetry.wheel.rl.toe"),
+        grip_fraction: generate_property_handle!("rf2-reader.telemetry.wheel.rl.grip_fraction"),
+        tire_load: generate_property_handle!("rf2-reader.telemetry.wheel.rl.tire_load"),
+        flat: generate_property_handle!("rf2-reader.telemetry.wheel.rl.flat"),
+        detached: generate_property_handle!("rf2-reader.telemetry.wheel.rl.detached"),
+        terrain_name: generate_property_handle!("rf2-reader.telemetry.wheel.rl.terrain_name"),
+    },
+    WheelProperties {
+        pressure: generate_property_handle!("rf2-reader.telemetry.wheel.rr.pressure"),
+        temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.rr.temp_left"),
+        temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.rr.temp_center"),
+        temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.rr.temp_right"),
+        carcass_temp: generate_property_handle!("rf2-reader.telemetry.wheel.rr.carcass_temp"),
+        inner_temp_left: generate_property_handle!("rf2-reader.telemetry.wheel.rr.inner_temp_left"),
+        inner_temp_center: generate_property_handle!("rf2-reader.telemetry.wheel.rr.inner_temp_center"),
+        inner_temp_right: generate_property_handle!("rf2-reader.telemetry.wheel.rr.inner_temp_right"),
+        wear: generate_property_handle!("rf2-reader.telemetry.wheel.rr.wear"),
+        brake_temp: generate_property_handle!("rf2-reader.telemetry.wheel.rr.brake_temp"),
+        brake_pressure: generate_property_handle!("rf2-reader.telemetry.wheel.rr.brake_pressure"),
+        suspension_deflection: generate_property_handle!("rf2-reader.telemetry.wheel.rr.suspension_deflection"),
+        ride_height: generate_property_handle!("rf2-reader.telemetry.wheel.rr.ride_height"),
+        camber: generate_property_handle!("rf2-reader.telemetry.wheel.rr.camber"),
+        toe: generate_property_handle!("rf2-reader.telemetry.wheel.rr.toe"),
+        grip_fraction: generate_property_handle!("rf2-reader.telemetry.wheel.rr.grip_fraction"),
+        tire_load: generate_property_handle!("rf2-reader.telemetry.wheel.rr.tire_load"),
+        flat: generate_property_handle!("rf2-reader.telemetry.wheel.rr.flat"),
+        detached: generate_property_handle!("rf2-reader.telemetry.wheel.rr.detached"),
+        terrain_name: generate_property_handle!("rf2-reader.telemetry.wheel.rr.terrain_name"),
+    },
+];
+
+/// Kelvin to Celsius, the tire temperatures are given in Kelvin
+const KELVIN_OFFSET: f64 = 273.15;
+
+// Graphics
+const P_GRAPHICS_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.graphics.update");
+const P_GRAPHICS_FOCUSED_VEHICLE_ID: PropertyHandle = generate_property_handle!("rf2-reader.graphics.focused_vehicle_id");
+const P_GRAPHICS_CAMERA_TYPE: PropertyHandle = generate_property_handle!("rf2-reader.graphics.camera_type");
+const P_TELEMETRY_VEHICLE_ID: PropertyHandle = generate_property_handle!("rf2-reader.telemetry.vehicle_id");
 
 // Scoring
 const P_SCORING_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.scoring.update");
 
+// Rules
+const P_RULES_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.rules.update");
+const P_RULES_STAGE: PropertyHandle = generate_property_handle!("rf2-reader.rules.stage");
+const P_RULES_SAFETY_CAR_EXISTS: PropertyHandle = generate_property_handle!("rf2-reader.rules.safety_car_exists");
+const P_RULES_SAFETY_CAR_ACTIVE: PropertyHandle = generate_property_handle!("rf2-reader.rules.safety_car_active");
+const P_RULES_SAFETY_CAR_LAPS: PropertyHandle = generate_property_handle!("rf2-reader.rules.safety_car_laps");
+const P_RULES_YELLOW_FLAG_DETECTED: PropertyHandle = generate_property_handle!("rf2-reader.rules.yellow_flag_detected");
+const P_RULES_YELLOW_FLAG_STATE: PropertyHandle = generate_property_handle!("rf2-reader.rules.yellow_flag_state");
+const P_RULES_YELLOW_FLAG_LAPS: PropertyHandle = generate_property_handle!("rf2-reader.rules.yellow_flag_laps");
+const P_RULES_MESSAGE: PropertyHandle = generate_property_handle!("rf2-reader.rules.message");
+
+const P_RULES_PLAYER_FROZEN_ORDER: PropertyHandle = generate_property_handle!("rf2-reader.rules.player.frozen_order");
+const P_RULES_PLAYER_PLACE: PropertyHandle = generate_property_handle!("rf2-reader.rules.player.place");
+const P_RULES_PLAYER_COLUMN_ASSIGNMENT: PropertyHandle = generate_property_handle!("rf2-reader.rules.player.column_assignment");
+const P_RULES_PLAYER_POSITION_ASSIGNMENT: PropertyHandle = generate_property_handle!("rf2-reader.rules.player.position_assignment");
+const P_RULES_PLAYER_PITS_OPEN: PropertyHandle = generate_property_handle!("rf2-reader.rules.player.pits_open");
+const P_RULES_PLAYER_MESSAGE: PropertyHandle = generate_property_handle!("rf2-reader.rules.player.message");
+
 
 /// Creates the property handles during init
-pub(crate) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+pub(crate) fn init_properties(handle: &PluginHandle, preflight: &share::Preflight) -> Result<(), String> {
     create_prop(handle, "extra", P_EXTRA, Property::None)?;
+    create_prop(handle, "game", P_GAME, Property::from_string(""))?;
+
+    // Setup checks
+    health::init_properties(handle, preflight)?;
     
     // Telemetry
     create_prop(handle, "telemetry.update", P_TELEMETRY_UPDATE, Property::Int(0))?;
     create_prop(handle, "debug.telemetry.time", P_DEBUG_TELEMETRY_TIME, Property::Duration(0))?;
+    create_prop(handle, "debug.invalid_values", P_DEBUG_INVALID_VALUES, Property::Int(0))?;
+    create_prop(handle, "debug.read.retried", P_DEBUG_READ_RETRIED, Property::Int(0))?;
+    create_prop(handle, "debug.read.torn", P_DEBUG_READ_TORN, Property::Int(0))?;
 
     create_prop(handle, "telemetry.session_elapsed_time", P_TELEMETRY_SESSION_ELAPSED_TIME, Property::Duration(0))?;
     create_prop(handle, "telemetry.lap_number", P_TELEMETRY_LAP_NUMBER, Property::Int(-1))?;
@@ -269,9 +362,74 @@ pub(crate) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
     create_prop(handle, "telemetry.engine.turbo_boost_pressure", P_TELEMETRY_ENGINE_TURBO_BOOST_PRESSURE, Property::Float(0.0))?;
     create_prop(handle, "telemetry.physical_steering_wheel_range", P_TELEMETRY_PHYSICAL_WHEEL_RANGE, Property::Float(0.0))?;
 
+    for (name, wheel) in WHEEL_NAMES.iter().zip(P_TELEMETRY_WHEELS.iter()) {
+        create_prop(handle, &format!("telemetry.wheel.{name}.pressure"), wheel.pressure, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.temp_left"), wheel.temp_left, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.temp_center"), wheel.temp_center, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.temp_right"), wheel.temp_right, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.carcass_temp"), wheel.carcass_temp, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.inner_temp_left"), wheel.inner_temp_left, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.inner_temp_center"), wheel.inner_temp_center, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.inner_temp_right"), wheel.inner_temp_right, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.wear"), wheel.wear, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.brake_temp"), wheel.brake_temp, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.brake_pressure"), wheel.brake_pressure, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.suspension_deflection"), wheel.suspension_deflection, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.ride_height"), wheel.ride_height, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.camber"), wheel.camber, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.toe"), wheel.toe, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.grip_fraction"), wheel.grip_fraction, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.tire_load"), wheel.tire_load, Property::Float(0.0))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.flat"), wheel.flat, Property::Bool(false))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.detached"), wheel.detached, Property::Bool(false))?;
+        create_prop(handle, &format!("telemetry.wheel.{name}.terrain_name"), wheel.terrain_name, Property::from_string(""))?;
+    }
+
+    // Graphics
+    create_prop(handle, "graphics.update", P_GRAPHICS_UPDATE, Property::Int(0))?;
+    create_prop(handle, "graphics.focused_vehicle_id", P_GRAPHICS_FOCUSED_VEHICLE_ID, Property::Int(-1))?;
+    create_prop(handle, "graphics.camera_type", P_GRAPHICS_CAMERA_TYPE, Property::Int(0))?;
+    create_prop(handle, "telemetry.vehicle_id", P_TELEMETRY_VEHICLE_ID, Property::Int(-1))?;
+
     // Scoring
     create_prop(handle, "scoring.update", P_SCORING_UPDATE, Property::Int(0))?;
 
+    standings::init_properties(handle)?;
+    session::init_properties(handle)?;
+    weather::init_properties(handle)?;
+
+    // Rules
+    create_prop(handle, "rules.update", P_RULES_UPDATE, Property::Int(0))?;
+    create_prop(handle, "rules.stage", P_RULES_STAGE, Property::Int(0))?;
+    create_prop(handle, "rules.safety_car_exists", P_RULES_SAFETY_CAR_EXISTS, Property::Bool(false))?;
+    create_prop(handle, "rules.safety_car_active", P_RULES_SAFETY_CAR_ACTIVE, Property::Bool(false))?;
+    create_prop(handle, "rules.safety_car_laps", P_RULES_SAFETY_CAR_LAPS, Property::Int(0))?;
+    create_prop(handle, "rules.yellow_flag_detected", P_RULES_YELLOW_FLAG_DETECTED, Property::Bool(false))?;
+    create_prop(handle, "rules.yellow_flag_state", P_RULES_YELLOW_FLAG_STATE, Property::Int(0))?;
+    create_prop(handle, "rules.yellow_flag_laps", P_RULES_YELLOW_FLAG_LAPS, Property::Int(0))?;
+    create_prop(handle, "rules.message", P_RULES_MESSAGE, Property::from_string(""))?;
+
+    create_prop(handle, "rules.player.frozen_order", P_RULES_PLAYER_FROZEN_ORDER, Property::Int(-1))?;
+    create_prop(handle, "rules.player.place", P_RULES_PLAYER_PLACE, Property::Int(0))?;
+    create_prop(handle, "rules.player.column_assignment", P_RULES_PLAYER_COLUMN_ASSIGNMENT, Property::Int(0))?;
+    create_prop(handle, "rules.player.position_assignment", P_RULES_PLAYER_POSITION_ASSIGNMENT, Property::Int(-1))?;
+    create_prop(handle, "rules.player.pits_open", P_RULES_PLAYER_PITS_OPEN, Property::Bool(false))?;
+    create_prop(handle, "rules.player.message", P_RULES_PLAYER_MESSAGE, Property::from_string(""))?;
+
+    // MultiRules
+    grid::init_properties(handle)?;
+
+    // Extended
+    extended::init_properties(handle)?;
+
+    damage::init_properties(handle)?;
+
+    // ForceFeedback
+    ffb::init_properties(handle)?;
+
+    // PitInfo
+    pit_menu::init_properties(handle)?;
+
     Ok(())
 }
 
@@ -285,44 +443,235 @@ fn create_prop(handle: &PluginHandle, prop_name: &str, prop_handle: PropertyHand
     }
 }
 
+/// Same as create_prop, just for events
+fn create_event(handle: &PluginHandle, event_name: &str, event_handle: EventHandle) -> Result<(),String> {
+    match handle.create_event(event_name, event_handle) {
+        DataStoreReturnCode::Ok => Ok(()),
+        e => Err(e.to_string())
+    }
+}
+
+/// Generates a property handle at runtime, for when we need too many to generate them at compile time
+/// prop_name is without the plugin prefix, same as for create_prop
+fn runtime_prop_handle(prop_name: &str) -> PropertyHandle {
+    // Can only fail with an invalid name, and all our names are made up of ascii and the prefix
+    PropertyHandle::new(&format!("rf2-reader.{prop_name}")).expect("Property names should be valid")
+}
+
 pub(crate) struct ReaderState {
     telemetry_update_version: u32,
+    /// Where the vehicle we read was last found in the telemetry page
+    telemetry_vehicle_index: usize,
+    /// Same for the player vehicle, when telemetry follows another car
+    player_vehicle_index: usize,
     telemetry_cache: TelemetryCache,
 
     scoring_update_version: u32,
+    standings: standings::StandingsState,
+    session_cache: session::SessionCache,
+    weather: weather::WeatherState,
+    weather_update_version: u32,
+
+    rules_update_version: u32,
+    rules_cache: RulesCache,
+
+    multi_rules_update_version: u32,
+    grid: grid::GridState,
+
+    extended_update_version: u32,
+    extended_cache: extended::ExtendedCache,
+    damage: damage::DamageState,
+
+    ffb: ffb::FfbState,
+    ffb_in_use: bool,
+
+    pit_info_update_version: u32,
+    pit_menu_cache: pit_menu::PitMenuCache,
+
+    graphics_update_version: u32,
+    /// Vehicle the camera is focused on, if the Graphics page is available
+    camera_vehicle_id: Option<i32>,
+    /// Telemetry is read for the vehicle in camera focus instead of the player
+    follow_camera: bool,
 
     player_vehicle_id: i32,
     version_last_increment: Option<std::time::Instant>,
+    read_stats: ReadStats,
+}
+
+#[derive(Default)]
+struct ReadStats {
+    retried: u64,
+    torn: u64,
 }
 
-impl Default for ReaderState {
-    fn default() -> Self {
+impl ReaderState {
+    pub(crate) fn new(config: &Config) -> Self {
         ReaderState {
             telemetry_update_version: 0,
+            telemetry_vehicle_index: 0,
+            player_vehicle_index: 0,
             telemetry_cache: TelemetryCache {
                 vehicle_name: String::new(),
                 track_name: String::new(),
                 front_tire_compound_name: String::new(),
-                rear_tire_compound_name: String::new()
+                rear_tire_compound_name: String::new(),
+                wheel_terrain_name: Default::default(),
             },
 
             scoring_update_version: 0,
+            standings: standings::StandingsState::default(),
+            session_cache: session::SessionCache::default(),
+            weather: weather::WeatherState::default(),
+            weather_update_version: 0,
+
+            rules_update_version: 0,
+            rules_cache: RulesCache {
+                message: String::new(),
+                player_message: String::new()
+            },
+
+            multi_rules_update_version: 0,
+            grid: grid::GridState::default(),
+
+            extended_update_version: 0,
+            extended_cache: extended::ExtendedCache::default(),
+            damage: damage::DamageState::default(),
+
+            ffb: ffb::FfbState::default(),
+            ffb_in_use: false,
+
+            pit_info_update_version: 0,
+            pit_menu_cache: pit_menu::PitMenuCache::default(),
+
+            graphics_update_version: 0,
+            camera_vehicle_id: None,
+            follow_camera: config.follow_camera,
+
             player_vehicle_id: 0,
             version_last_increment: None,
+            read_stats: ReadStats::default(),
 
         }
     }
 }
 
+impl ReaderState {
+    /// Versions of the pages we read last, for the scheduler to learn the update rates from
+    pub(crate) fn page_versions(&self) -> PageVersions {
+        PageVersions {
+            telemetry: self.telemetry_update_version,
+            scoring: self.scoring_update_version,
+            rules: self.rules_update_version,
+            extended: self.extended_update_version,
+            pit_info: self.pit_info_update_version,
+            weather: self.weather_update_version,
+        }
+    }
+
+    /// When we next have to read, regardless of page updates (ForceFeedback has no versions)
+    /// Stands in for reading a telemetry update
+    #[cfg(test)]
+    pub(crate) fn set_telemetry_version(&mut self, version: u32) {
+        self.telemetry_update_version = version;
+    }
+
+    /// None if there is nothing to sample
+    pub(crate) fn next_deadline(&self) -> Option<std::time::Instant> {
+        self.ffb.next_sample()
+    }
+
+    /// ForceFeedback is only sampled while its properties are in use (see control::BufferUses)
+    pub(crate) fn set_ffb_in_use(&mut self, in_use: bool) {
+        self.ffb_in_use = in_use;
+    }
+
+    /// The vehicle we publish telemetry for
+    fn telemetry_vehicle_id(&self) -> i32 {
+        match self.camera_vehicle_id {
+            Some(id) if self.follow_camera && id >= 0 => id,
+            _ => self.player_vehicle_id
+        }
+    }
+}
+
+/// Enum values outside their variants since the plugin loaded, shared by all pages
+static INVALID_VALUES: AtomicU64 = AtomicU64::new(0);
+
+/// Passes through the result of a from_raw, counting it into the debug properties if the value was invalid
+fn count_invalid<T>(handle: &PluginHandle, value: Option<T>) -> Option<T> {
+    if value.is_none() {
+        let count = INVALID_VALUES.fetch_add(1, Ordering::Relaxed) + 1;
+        handle.update_property(P_DEBUG_INVALID_VALUES, Property::from(count));
+    }
+
+    value
+}
+
+/// Counts the outcome of a SharedMemory read into the debug properties,
+/// returns the copy if there was an untorn one
+fn track_read<R>(handle: &PluginHandle, res: Result<(R, u32), u32>, stats: &mut ReadStats) -> Option<R> {
+    match res {
+        Ok((res, 0)) => Some(res),
+        Ok((res, _)) => {
+            stats.retried += 1;
+            handle.update_property(P_DEBUG_READ_RETRIED, Property::from(stats.retried));
+            Some(res)
+        },
+        Err(_) => {
+            stats.torn += 1;
+            handle.update_property(P_DEBUG_READ_TORN, Property::from(stats.torn));
+            None
+        }
+    }
+}
+
+/// Copies the telemetry of the vehicle with the id, starting at the index it was found at last time
+/// (as it usually stays there), only searching the other vehicles if it moved
+fn read_vehicle_by_id(handle: &PluginHandle, mount: &MapHolder, id: i32, last_index: &mut usize, stats: &mut ReadStats) -> Option<VehicleRead> {
+    let first = track_read(handle, mount.telemetry.read_vehicle(*last_index), stats)?;
+
+    let num_vehicles = if first.num_vehicles >= 0 && (first.num_vehicles as usize) <= MAX_MAPPED_VEHICLES {
+        first.num_vehicles as usize
+    } else {
+        MAX_MAPPED_VEHICLES
+    };
+
+    if first.vehicle.id == id && *last_index < num_vehicles {
+        return Some(first);
+    }
+
+    for index in (0..num_vehicles).filter(|index| *index != *last_index) {
+        let read = track_read(handle, mount.telemetry.read_vehicle(index), stats)?;
+        if read.vehicle.id == id {
+            *last_index = index;
+            return Some(read);
+        }
+    }
+
+    None
+}
+
+/// Publishes the setup checks again
+pub(crate) fn update_health(handle: &PluginHandle, preflight: &share::Preflight) {
+    health::update_properties(handle, preflight);
+}
+
+/// Publishes which game we are reading from, None when it stopped
+pub(crate) fn set_game(handle: &PluginHandle, game: Option<&share::GameProfile>) {
+    handle.update_property(P_GAME, Property::from_string(game.map(|game| game.name).unwrap_or("")));
+}
+
 /// Reads memory map
 /// Ok(game running), if in doubt return false
 pub(crate) fn update_properties(handle: &PluginHandle, mount: &MapHolder, state: &mut ReaderState, runner_checkgame_state: &mut share::GameRunningHelperState) -> Result<bool, String> {
-    if state.scoring_update_version != mount.scoring.get().header.version_update_begin {
-        let update = mount.scoring.get().clone();
+    // ForceFeedback runs at 400Hz, so it is sampled on it's own timer instead of waiting on any page
+    ffb::sample(handle, mount, state.ffb_in_use, &mut state.ffb);
 
-        // We clone, so read the entire memory map, to insure a none torne frame
-        // we check the begin and end version, and only update if they match
-        if update.header.version_update_begin == update.header.version_update_end {
+    // Every page is copied with SharedMemory::snapshot, which retries torn frames,
+    // so we only have to look at the version to know if there is anything new
+    if state.scoring_update_version != mount.scoring.version() {
+        if let Some(update) = track_read(handle, mount.scoring.snapshot(), &mut state.read_stats) {
             state.scoring_update_version = update.header.version_update_begin;
 
             read_scoring(handle, update, state);
@@ -331,62 +680,106 @@ pub(crate) fn update_properties(handle: &PluginHandle, mount: &MapHolder, state:
         }
     }
 
+    if let Some(rules) = mount.rules.as_ref().filter(|page| state.rules_update_version != page.version()) {
+        if let Some(update) = track_read(handle, rules.snapshot(), &mut state.read_stats) {
+            state.rules_update_version = update.header.version_update_begin;
 
-    let telemetry_timing = std::time::Instant::now();
-    if state.telemetry_update_version != mount.telemetry.get().header.version_update_begin {
+            read_rules(handle, update, state);
+
+            handle.update_property(P_RULES_UPDATE, Property::from(state.rules_update_version));
+        }
+    }
 
-        // Reference into memory that can be actively changed
-        // Not great, but we hold this for a moment to find the player car
-        // so we don't have to clone all Telemetry, just that of the player car
-        let unstable = mount.telemetry.get();
-        let begin = unstable.header.version_update_begin;
-        let num_vehicles = if unstable.num_vehicles >= 0 && (unstable.num_vehicles as usize) <= MAX_MAPPED_VEHICLES {
-            unstable.num_vehicles.clone() as usize
-        } else {
-            MAX_MAPPED_VEHICLES
-        };
+    if let Some(multi_rules) = mount.multi_rules.as_ref().filter(|page| state.multi_rules_update_version != page.version()) {
+        if let Some(update) = track_read(handle, multi_rules.snapshot(), &mut state.read_stats) {
+            state.multi_rules_update_version = update.header.version_update_begin;
 
-        let mut not_found = true;
+            grid::read_multi_rules(handle, &update, &mut state.grid);
+        }
+    }
 
-        for i in 0..num_vehicles {
-            let veh = unstable.vehicles[i];
+    if state.extended_update_version != mount.extended.version() {
+        if let Some(update) = track_read(handle, mount.extended.snapshot(), &mut state.read_stats) {
+            state.extended_update_version = update.header.version_update_begin;
+
+            extended::read_extended(handle, &update, &mut state.extended_cache);
+            damage::read_tracked_damages(handle, &update, state.player_vehicle_id, &mut state.damage);
+        }
+    }
 
-            // Can we use it as an id to straight index? TODO
-            if veh.id == state.player_vehicle_id {
-                let update = veh.clone();
+    if let Some(pit_info) = mount.pit_info.as_ref().filter(|page| state.pit_info_update_version != page.version()) {
+        if let Some(update) = track_read(handle, pit_info.snapshot(), &mut state.read_stats) {
+            state.pit_info_update_version = update.header.version_update_begin;
 
-                // After we cloned only the vehicle we need we check the update id for change
-                // we check the begin and end version, and only update if they match
-                if begin == mount.telemetry.get().header.version_update_end {
-                    state.telemetry_update_version = begin;
+            pit_menu::read_pit_menu(handle, &update.pit_menu, state.pit_info_update_version, &mut state.pit_menu_cache);
+        }
+    }
 
-                    read_telemetry(handle, update, &mut state.telemetry_cache);
 
-                    handle.update_property(P_TELEMETRY_UPDATE, Property::from(begin));
-                    handle.update_property(P_DEBUG_TELEMETRY_TIME, Property::from(std::time::Instant::now() - telemetry_timing));
+    // Graphics contains the car the player is currently spectating,
+    // but graphics is also not subscribed by default, so the version stays 0 until the game writes it
+    if let Some(graphics) = &mount.graphics {
+        let begin = graphics.version();
+        if begin != 0 && state.graphics_update_version != begin {
+            if let Some(update) = track_read(handle, graphics.snapshot(), &mut state.read_stats) {
+                state.graphics_update_version = update.header.version_update_begin;
+
+                let info = update.graphics_info;
+                state.camera_vehicle_id = Some(info.id);
+                handle.update_property(P_GRAPHICS_FOCUSED_VEHICLE_ID, Property::from(info.id));
+                handle.update_property(P_GRAPHICS_CAMERA_TYPE, Property::from(info.camera_type));
+                handle.update_property(P_GRAPHICS_UPDATE, Property::from(state.graphics_update_version));
+            }
+        }
+    }
 
-                    // Reason we are doing this is to prevent a torn frame from deadlocking us
-                    not_found = false;
-                }
+    // Weather is unsubscribed by default too, and only updates at 1Hz
+    if let Some(weather) = &mount.weather {
+        let begin = weather.version();
+        if begin != 0 && state.weather_update_version != begin {
+            if let Some(update) = track_read(handle, weather.snapshot(), &mut state.read_stats) {
+                state.weather_update_version = update.header.version_update_begin;
 
-                break;
+                weather::read_target(handle, &update);
             }
         }
+    }
+
+    let telemetry_timing = std::time::Instant::now();
+    if state.telemetry_update_version != mount.telemetry.version() {
+        let target_id = state.telemetry_vehicle_id();
+
+        match read_vehicle_by_id(handle, mount, target_id, &mut state.telemetry_vehicle_index, &mut state.read_stats) {
+            Some(VehicleRead { version: begin, vehicle: update, .. }) => {
+                state.telemetry_update_version = begin;
+
+                read_telemetry(handle, update, &mut state.telemetry_cache);
+
+                // Damage and the ForceFeedback lap are always the players car, even while telemetry follows the camera
+                if target_id == state.player_vehicle_id {
+                    damage::read_player_telemetry(handle, &update);
+                    ffb::lap_update(handle, update.lap_number, &mut state.ffb);
+                } else if let Some(player) = read_vehicle_by_id(handle, mount, state.player_vehicle_id, &mut state.player_vehicle_index, &mut state.read_stats) {
+                    damage::read_player_telemetry(handle, &player.vehicle);
+                    ffb::lap_update(handle, player.vehicle.lap_number, &mut state.ffb);
+                }
+                handle.update_property(P_TELEMETRY_VEHICLE_ID, Property::from(target_id));
+
+                handle.update_property(P_TELEMETRY_UPDATE, Property::from(begin));
+                handle.update_property(P_DEBUG_TELEMETRY_TIME, Property::from(std::time::Instant::now() - telemetry_timing));
 
-        if not_found && state.version_last_increment.is_none() {
-            state.version_last_increment = Some(std::time::Instant::now());
-        } else if !not_found {
-            state.version_last_increment = None;
+                state.version_last_increment = None;
+            },
+            // Vehicle not found, or the frame was torn, neither should deadlock us
+            _ => if state.version_last_increment.is_none() {
+                state.version_last_increment = Some(std::time::Instant::now());
+            }
         }
 
     } else if state.version_last_increment.is_none() {
         state.version_last_increment = Some(std::time::Instant::now());
     }
 
-    // Graphics contains the car the player is currently spectating,
-    // but graphics is also not subscribed by default
-    // *And the implementation in data.rs is lacking*
-    
 
     // Triggering game running check due to lack of updates 
     if let Some(last) = state.version_last_increment {
@@ -411,7 +804,8 @@ struct TelemetryCache {
     vehicle_name: String,
     track_name: String,
     front_tire_compound_name: String,
-    rear_tire_compound_name: String
+    rear_tire_compound_name: String,
+    wheel_terrain_name: [String; 4],
 }
 
 fn read_telemetry(handle: &PluginHandle, update: PageVehicleTelemetry, cache: &mut TelemetryCache) {
@@ -469,10 +863,14 @@ fn read_telemetry(handle: &PluginHandle, update: PageVehicleTelemetry, cache: &m
     handle.update_property(P_TELEMETRY_FUEL_CAPACITY, Property::from(update.fuel_capacity)); // infrequently
     handle.update_property(P_TELEMETRY_FRONT_FLAP_ACTIVATED, Property::from(update.front_flap_activated != 0));
     handle.update_property(P_TELEMETRY_REAR_FLAP_ACTIVATED, Property::from(update.rear_flap_activated != 0));
-    handle.update_property(P_TELEMETRY_REAR_FLAP_DETECTED, Property::from(update.rear_flap_legal_status == RF2RearFlapLegalStatus::DetectedButNotAllowedYet));
-    handle.update_property(P_TELEMETRY_REAR_FLAP_ALLOWED, Property::from(update.rear_flap_legal_status == RF2RearFlapLegalStatus::Allowed));
-    handle.update_property(P_TELEMETRY_ENGINE_IGNITION, Property::from(update.ignition_starter != RF2IgnitionStarterStatus::Off));
-    handle.update_property(P_TELEMETRY_ENGINE_STARTER, Property::from(update.ignition_starter == RF2IgnitionStarterStatus::IgnitionAndStarter));
+
+    // Values outside the enums are treated as the off state
+    let rear_flap = count_invalid(handle, update.rear_flap_legal_status());
+    let ignition = count_invalid(handle, update.ignition_starter());
+    handle.update_property(P_TELEMETRY_REAR_FLAP_DETECTED, Property::from(rear_flap == Some(RF2RearFlapLegalStatus::DetectedButNotAllowedYet)));
+    handle.update_property(P_TELEMETRY_REAR_FLAP_ALLOWED, Property::from(rear_flap == Some(RF2RearFlapLegalStatus::Allowed)));
+    handle.update_property(P_TELEMETRY_ENGINE_IGNITION, Property::from(ignition.is_some_and(|status| status != RF2IgnitionStarterStatus::Off)));
+    handle.update_property(P_TELEMETRY_ENGINE_STARTER, Property::from(ignition == Some(RF2IgnitionStarterStatus::IgnitionAndStarter)));
 
     help_read_string(handle, &update.front_tire_compound_name, &mut cache.front_tire_compound_name, P_TELEMETRY_FRONT_TIRE_COMPOUND_NAME);
     help_read_string(handle, &update.rear_tire_compound_name, &mut cache.rear_tire_compound_name, P_TELEMETRY_REAR_TIRE_COMPOUND_NAME);
@@ -484,10 +882,15 @@ fn read_telemetry(handle: &PluginHandle, update: PageVehicleTelemetry, cache: &m
     handle.update_property(P_TELEMETRY_ENGINE_TURBO_BOOST_PRESSURE, Property::from(update.turbo_boost_pressure));
     handle.update_property(P_TELEMETRY_PHYSICAL_WHEEL_RANGE, Property::from(update.physical_steering_wheel_range)); // infrequently
 
+    let wheels = update.wheels;
+    for ((wheel, props), terrain_cache) in wheels.iter().zip(P_TELEMETRY_WHEELS.iter()).zip(cache.wheel_terrain_name.iter_mut()) {
+        read_wheel(handle, wheel, props, terrain_cache);
+    }
+
     // handle.log_info(format!("Time: {}", handle.get_property_value(P_TELEMETRY_SESSION_ELAPSED_TIME).unwrap().to_duration().unwrap().0.as_secs_f64()));
 }
 
-fn read_scoring(_handle: &PluginHandle, update: PageScoring, state: &mut ReaderState) {
+fn read_scoring(handle: &PluginHandle, update: PageScoring, state: &mut ReaderState) {
     let num_vehicles = if update.scoring_info.num_vehicles >= 0 && (update.scoring_info.num_vehicles as usize) <= MAX_MAPPED_VEHICLES {
         update.scoring_info.num_vehicles as usize
     } else {
@@ -500,17 +903,84 @@ fn read_scoring(_handle: &PluginHandle, update: PageScoring, state: &mut ReaderS
         if veh.is_player != 0 {
             state.player_vehicle_id = veh.id;
         }
-
-        
-        
     }
 
+    session::read_session(handle, &update.scoring_info, &mut state.session_cache);
+    weather::read_weather(handle, &update.scoring_info, &mut state.weather);
+    standings::read_standings(handle, &update, num_vehicles, &mut state.standings);
+}
+
+fn read_wheel(handle: &PluginHandle, wheel: &PageWheelTelemetry, props: &WheelProperties, terrain_cache: &mut String) {
+    let temperature = wheel.temperature;
+    let inner_temperature = wheel.tire_inner_layer_temperature;
+
+    handle.update_property(props.pressure, Property::from(wheel.pressure));
+    handle.update_property(props.temp_left, Property::from(temperature[0] - KELVIN_OFFSET));
+    handle.update_property(props.temp_center, Property::from(temperature[1] - KELVIN_OFFSET));
+    handle.update_property(props.temp_right, Property::from(temperature[2] - KELVIN_OFFSET));
+    handle.update_property(props.carcass_temp, Property::from(wheel.tire_carcass_temperature - KELVIN_OFFSET));
+    handle.update_property(props.inner_temp_left, Property::from(inner_temperature[0] - KELVIN_OFFSET));
+    handle.update_property(props.inner_temp_center, Property::from(inner_temperature[1] - KELVIN_OFFSET));
+    handle.update_property(props.inner_temp_right, Property::from(inner_temperature[2] - KELVIN_OFFSET));
+    handle.update_property(props.wear, Property::from(wheel.wear));
+
+    handle.update_property(props.brake_temp, Property::from(wheel.brake_temp));
+    handle.update_property(props.brake_pressure, Property::from(wheel.brake_pressure));
+    handle.update_property(props.suspension_deflection, Property::from(wheel.suspension_deflection));
+    handle.update_property(props.ride_height, Property::from(wheel.ride_height));
+    handle.update_property(props.camber, Property::from(wheel.camber));
+    handle.update_property(props.toe, Property::from(wheel.toe));
+    handle.update_property(props.grip_fraction, Property::from(wheel.grip_fract));
+    handle.update_property(props.tire_load, Property::from(wheel.tire_load));
+
+    handle.update_property(props.flat, Property::from(wheel.flat != 0));
+    handle.update_property(props.detached, Property::from(wheel.detached != 0));
+    help_read_string(handle, &wheel.terrain_name, terrain_cache, props.terrain_name);
+}
+
+struct RulesCache {
+    message: String,
+    player_message: String
 }
 
+fn read_rules(handle: &PluginHandle, update: PageRules, state: &mut ReaderState) {
+    let rules = update.track_rules;
+
+    handle.update_property(P_RULES_STAGE, Property::from(rules.stage));
+    handle.update_property(P_RULES_SAFETY_CAR_EXISTS, Property::from(rules.safety_car_exists != 0));
+    handle.update_property(P_RULES_SAFETY_CAR_ACTIVE, Property::from(rules.safety_car_active != 0));
+    handle.update_property(P_RULES_SAFETY_CAR_LAPS, Property::from(rules.safety_car_laps));
+    handle.update_property(P_RULES_YELLOW_FLAG_DETECTED, Property::from(rules.yellow_flag_detected != 0));
+    handle.update_property(P_RULES_YELLOW_FLAG_STATE, Property::from(rules.yellow_flag_state));
+    handle.update_property(P_RULES_YELLOW_FLAG_LAPS, Property::from(rules.yellow_flag_laps));
+    help_read_string(handle, &rules.message, &mut state.rules_cache.message, P_RULES_MESSAGE);
+
+    let num_participants = if rules.num_participants >= 0 && (rules.num_participants as usize) <= MAX_MAPPED_VEHICLES {
+        rules.num_participants as usize
+    } else {
+        MAX_MAPPED_VEHICLES
+    };
+
+    for i in 0..num_participants {
+        let part = update.participants[i];
+
+        if part.id == state.player_vehicle_id {
+            handle.update_property(P_RULES_PLAYER_FROZEN_ORDER, Property::from(part.frozen_order));
+            handle.update_property(P_RULES_PLAYER_PLACE, Property::from(part.place));
+            handle.update_property(P_RULES_PLAYER_COLUMN_ASSIGNMENT, Property::from(part.column_assignment));
+            handle.update_property(P_RULES_PLAYER_POSITION_ASSIGNMENT, Property::from(part.position_assignment));
+            // Input is 2=false or 3=true, but if another plugin edited it it can be 0=false or 1=true
+            handle.update_property(P_RULES_PLAYER_PITS_OPEN, Property::from(part.pits_open == 1 || part.pits_open == 3));
+            help_read_string(handle, &part.message, &mut state.rules_cache.player_message, P_RULES_PLAYER_MESSAGE);
+
+            break;
+        }
+    }
+}
 
 #[inline]
 fn help_read_string(handle: &PluginHandle, slice: &[u8], cache: &mut String, property: PropertyHandle) {
-    let read = String::from_utf8_lossy(slice);
+    let read = read_c_string(slice);
 
     if read != cache.as_str() {
         // Change detected
@@ -520,3 +990,29 @@ fn help_read_string(handle: &PluginHandle, slice: &[u8], cache: &mut String, pro
         handle.update_property(property, Property::Str(fix));
     }
 }
+
+/// The game writes null terminated strings into fixed buffers, and leaves whatever was in the buffer
+/// before after the terminator (like the end of a longer previous name). Only the text up to the first NUL is the value
+fn read_c_string(slice: &[u8]) -> std::borrow::Cow<'_, str> {
+    let end = slice.iter().position(|c| *c == 0).unwrap_or(slice.len());
+    String::from_utf8_lossy(&slice[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_end_at_the_first_nul() {
+        // "Spa" written over a previous "Monza", as the game leaves it in the buffer
+        let mut buffer = [0u8; 16];
+        buffer[..5].copy_from_slice(b"Monza");
+        buffer[..4].copy_from_slice(b"Spa\0");
+        assert_eq!(read_c_string(&buffer), "Spa");
+
+        assert_eq!(read_c_string(b"\0Garbage"), "");
+        assert_eq!(read_c_string(b""), "");
+        // Without a terminator the whole buffer is the string
+        assert_eq!(read_c_string(b"Full"), "Full");
+    }
+}
@@ -0,0 +1,119 @@
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::data::{PageExtended, PageVehicleTelemetry, MAX_MAPPED_IDS};
+
+use super::{create_prop, runtime_prop_handle};
+
+// Player, from telemetry
+/// dent severity at 8 locations around the car (0=none, 1=some, 2=more)
+const P_DAMAGE_DENT_SEVERITY: [PropertyHandle; 8] = [
+    generate_property_handle!("rf2-reader.damage.dent_severity.0"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.1"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.2"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.3"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.4"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.5"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.6"),
+    generate_property_handle!("rf2-reader.damage.dent_severity.7"),
+];
+const P_DAMAGE_DETACHED: PropertyHandle = generate_property_handle!("rf2-reader.damage.detached");
+const P_DAMAGE_LAST_IMPACT_TIME: PropertyHandle = generate_property_handle!("rf2-reader.damage.last_impact_time");
+const P_DAMAGE_LAST_IMPACT_MAGNITUDE: PropertyHandle = generate_property_handle!("rf2-reader.damage.last_impact_magnitude");
+const P_DAMAGE_LAST_IMPACT_POS_X: PropertyHandle = generate_property_handle!("rf2-reader.damage.last_impact_pos_x");
+const P_DAMAGE_LAST_IMPACT_POS_Y: PropertyHandle = generate_property_handle!("rf2-reader.damage.last_impact_pos_y");
+const P_DAMAGE_LAST_IMPACT_POS_Z: PropertyHandle = generate_property_handle!("rf2-reader.damage.last_impact_pos_z");
+
+// Player, from extended
+const P_DAMAGE_MAX_IMPACT: PropertyHandle = generate_property_handle!("rf2-reader.damage.max_impact");
+const P_DAMAGE_ACCUMULATED_IMPACT: PropertyHandle = generate_property_handle!("rf2-reader.damage.accumulated_impact");
+
+/// Handles for the damage of every slot id, as tracked by the Extended page
+struct VehicleDamageProperties {
+    max_impact: PropertyHandle,
+    accumulated_impact: PropertyHandle,
+}
+
+impl VehicleDamageProperties {
+    fn new(id: usize) -> Self {
+        VehicleDamageProperties {
+            max_impact: runtime_prop_handle(&format!("damage.vehicle.{id}.max_impact")),
+            accumulated_impact: runtime_prop_handle(&format!("damage.vehicle.{id}.accumulated_impact")),
+        }
+    }
+}
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    for (i, prop) in P_DAMAGE_DENT_SEVERITY.iter().enumerate() {
+        create_prop(handle, &format!("damage.dent_severity.{i}"), *prop, Property::Int(0))?;
+    }
+    create_prop(handle, "damage.detached", P_DAMAGE_DETACHED, Property::Bool(false))?;
+    create_prop(handle, "damage.last_impact_time", P_DAMAGE_LAST_IMPACT_TIME, Property::Duration(0))?;
+    create_prop(handle, "damage.last_impact_magnitude", P_DAMAGE_LAST_IMPACT_MAGNITUDE, Property::Float(0.0))?;
+    create_prop(handle, "damage.last_impact_pos_x", P_DAMAGE_LAST_IMPACT_POS_X, Property::Float(0.0))?;
+    create_prop(handle, "damage.last_impact_pos_y", P_DAMAGE_LAST_IMPACT_POS_Y, Property::Float(0.0))?;
+    create_prop(handle, "damage.last_impact_pos_z", P_DAMAGE_LAST_IMPACT_POS_Z, Property::Float(0.0))?;
+
+    create_prop(handle, "damage.max_impact", P_DAMAGE_MAX_IMPACT, Property::Float(0.0))?;
+    create_prop(handle, "damage.accumulated_impact", P_DAMAGE_ACCUMULATED_IMPACT, Property::Float(0.0))?;
+
+    for id in 0..MAX_MAPPED_IDS {
+        let props = VehicleDamageProperties::new(id);
+        create_prop(handle, &format!("damage.vehicle.{id}.max_impact"), props.max_impact, Property::Float(0.0))?;
+        create_prop(handle, &format!("damage.vehicle.{id}.accumulated_impact"), props.accumulated_impact, Property::Float(0.0))?;
+    }
+
+    Ok(())
+}
+
+pub(super) struct DamageState {
+    vehicles: Vec<VehicleDamageProperties>,
+    /// Last published (max, accumulated) per slot id, most of them never change, so we only
+    /// send updates on change
+    last: Vec<(f64, f64)>,
+}
+
+impl Default for DamageState {
+    fn default() -> Self {
+        DamageState {
+            vehicles: (0..MAX_MAPPED_IDS).map(VehicleDamageProperties::new).collect(),
+            last: vec![(0.0, 0.0); MAX_MAPPED_IDS],
+        }
+    }
+}
+
+/// Damage of the player car contained in the telemetry
+pub(super) fn read_player_telemetry(handle: &PluginHandle, update: &PageVehicleTelemetry) {
+    let dents = update.dent_severity;
+    for (dent, prop) in dents.iter().zip(P_DAMAGE_DENT_SEVERITY.iter()) {
+        handle.update_property(*prop, Property::from(*dent));
+    }
+    handle.update_property(P_DAMAGE_DETACHED, Property::from(update.detached != 0));
+
+    handle.update_property(P_DAMAGE_LAST_IMPACT_TIME, Property::from_sec(update.last_impact_et.max(0.0)));
+    handle.update_property(P_DAMAGE_LAST_IMPACT_MAGNITUDE, Property::from(update.last_impact_magnitude));
+    let pos = update.last_impact_pos;
+    handle.update_property(P_DAMAGE_LAST_IMPACT_POS_X, Property::from(pos.x));
+    handle.update_property(P_DAMAGE_LAST_IMPACT_POS_Y, Property::from(pos.y));
+    handle.update_property(P_DAMAGE_LAST_IMPACT_POS_Z, Property::from(pos.z));
+}
+
+/// Impact tracking of the whole field from the Extended page
+pub(super) fn read_tracked_damages(handle: &PluginHandle, update: &PageExtended, player_vehicle_id: i32, state: &mut DamageState) {
+    let damages = update.tracked_damages;
+
+    if player_vehicle_id >= 0 {
+        let player = damages[player_vehicle_id as usize % MAX_MAPPED_IDS];
+        handle.update_property(P_DAMAGE_MAX_IMPACT, Property::from(player.max_impact_magnitude));
+        handle.update_property(P_DAMAGE_ACCUMULATED_IMPACT, Property::from(player.accumulated_impact_magnitude));
+    }
+
+    for ((damage, props), last) in damages.iter().zip(state.vehicles.iter()).zip(state.last.iter_mut()) {
+        let current = (damage.max_impact_magnitude, damage.accumulated_impact_magnitude);
+
+        if current != *last {
+            handle.update_property(props.max_impact, Property::from(current.0));
+            handle.update_property(props.accumulated_impact, Property::from(current.1));
+            *last = current;
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use datarace_plugin_api::{macros::{generate_event_handle, generate_property_handle}, wrappers::{EventHandle, PluginHandle, Property, PropertyHandle}};
+
+use crate::data::{PageExtended, PagePhysicsOptions};
+
+use super::{create_event, create_prop, help_read_string};
+
+const P_EXTENDED_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.extended.update");
+const P_EXTENDED_VERSION: PropertyHandle = generate_property_handle!("rf2-reader.extended.version");
+const P_EXTENDED_SESSION_STARTED: PropertyHandle = generate_property_handle!("rf2-reader.extended.session_started");
+const P_EXTENDED_PIT_SPEED_LIMIT: PropertyHandle = generate_property_handle!("rf2-reader.extended.pit_speed_limit");
+
+// Messages
+const P_MESSAGE_STATUS: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.status");
+const P_MESSAGE_LAST_HISTORY: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.last_history");
+const P_MESSAGE_DISPLAYED: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.displayed");
+const P_MESSAGE_LSI_PHASE: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.lsi_phase");
+const P_MESSAGE_LSI_PIT_STATE: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.lsi_pit_state");
+const P_MESSAGE_LSI_ORDER_INSTRUCTION: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.lsi_order_instruction");
+const P_MESSAGE_LSI_RULES_INSTRUCTION: PropertyHandle = generate_property_handle!("rf2-reader.extended.message.lsi_rules_instruction");
+
+// These fire whenever the game updates the message, even if the text stayed the same
+const E_MESSAGE_STATUS: EventHandle = generate_event_handle!("rf2-reader.extended.message.status");
+const E_MESSAGE_LAST_HISTORY: EventHandle = generate_event_handle!("rf2-reader.extended.message.last_history");
+const E_MESSAGE_DISPLAYED: EventHandle = generate_event_handle!("rf2-reader.extended.message.displayed");
+const E_MESSAGE_LSI_PHASE: EventHandle = generate_event_handle!("rf2-reader.extended.message.lsi_phase");
+const E_MESSAGE_LSI_PIT_STATE: EventHandle = generate_event_handle!("rf2-reader.extended.message.lsi_pit_state");
+const E_MESSAGE_LSI_ORDER_INSTRUCTION: EventHandle = generate_event_handle!("rf2-reader.extended.message.lsi_order_instruction");
+const E_MESSAGE_LSI_RULES_INSTRUCTION: EventHandle = generate_event_handle!("rf2-reader.extended.message.lsi_rules_instruction");
+
+// Physics options
+const P_PHYSICS_TRACTION_CONTROL: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.traction_control");
+const P_PHYSICS_ANTI_LOCK_BRAKES: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.anti_lock_brakes");
+const P_PHYSICS_STABILITY_CONTROL: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.stability_control");
+const P_PHYSICS_AUTO_SHIFT: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_shift");
+const P_PHYSICS_AUTO_CLUTCH: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_clutch");
+const P_PHYSICS_INVULNERABLE: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.invulnerable");
+const P_PHYSICS_OPPOSITE_LOCK: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.opposite_lock");
+const P_PHYSICS_STEERING_HELP: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.steering_help");
+const P_PHYSICS_BRAKING_HELP: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.braking_help");
+const P_PHYSICS_SPIN_RECOVERY: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.spin_recovery");
+const P_PHYSICS_AUTO_PIT: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_pit");
+const P_PHYSICS_AUTO_LIFT: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_lift");
+const P_PHYSICS_AUTO_BLIP: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_blip");
+
+const P_PHYSICS_FUEL_MULT: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.fuel_mult");
+const P_PHYSICS_TIRE_MULT: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.tire_mult");
+const P_PHYSICS_MECH_FAIL: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.mech_fail");
+const P_PHYSICS_ALLOW_PITCREW_PUSH: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.allow_pitcrew_push");
+const P_PHYSICS_REPEAT_SHIFTS: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.repeat_shifts");
+const P_PHYSICS_HOLD_CLUTCH: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.hold_clutch");
+const P_PHYSICS_AUTO_REVERSE: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_reverse");
+const P_PHYSICS_ALTERNATE_NEUTRAL: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.alternate_neutral");
+const P_PHYSICS_AI_CONTROL: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.ai_control");
+
+const P_PHYSICS_MANUAL_SHIFT_OVERRIDE_TIME: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.manual_shift_override_time");
+const P_PHYSICS_AUTO_SHIFT_OVERRIDE_TIME: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.auto_shift_override_time");
+const P_PHYSICS_SPEED_SENSITIVE_STEERING: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.speed_sensitive_steering");
+const P_PHYSICS_STEER_RATIO_SPEED: PropertyHandle = generate_property_handle!("rf2-reader.extended.physics.steer_ratio_speed");
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    create_prop(handle, "extended.update", P_EXTENDED_UPDATE, Property::Int(0))?;
+    create_prop(handle, "extended.version", P_EXTENDED_VERSION, Property::from_string(""))?;
+    create_prop(handle, "extended.session_started", P_EXTENDED_SESSION_STARTED, Property::Bool(false))?;
+    create_prop(handle, "extended.pit_speed_limit", P_EXTENDED_PIT_SPEED_LIMIT, Property::Float(0.0))?;
+
+    // Messages
+    create_prop(handle, "extended.message.status", P_MESSAGE_STATUS, Property::from_string(""))?;
+    create_prop(handle, "extended.message.last_history", P_MESSAGE_LAST_HISTORY, Property::from_string(""))?;
+    create_prop(handle, "extended.message.displayed", P_MESSAGE_DISPLAYED, Property::from_string(""))?;
+    create_prop(handle, "extended.message.lsi_phase", P_MESSAGE_LSI_PHASE, Property::from_string(""))?;
+    create_prop(handle, "extended.message.lsi_pit_state", P_MESSAGE_LSI_PIT_STATE, Property::from_string(""))?;
+    create_prop(handle, "extended.message.lsi_order_instruction", P_MESSAGE_LSI_ORDER_INSTRUCTION, Property::from_string(""))?;
+    create_prop(handle, "extended.message.lsi_rules_instruction", P_MESSAGE_LSI_RULES_INSTRUCTION, Property::from_string(""))?;
+
+    create_event(handle, "extended.message.status", E_MESSAGE_STATUS)?;
+    create_event(handle, "extended.message.last_history", E_MESSAGE_LAST_HISTORY)?;
+    create_event(handle, "extended.message.displayed", E_MESSAGE_DISPLAYED)?;
+    create_event(handle, "extended.message.lsi_phase", E_MESSAGE_LSI_PHASE)?;
+    create_event(handle, "extended.message.lsi_pit_state", E_MESSAGE_LSI_PIT_STATE)?;
+    create_event(handle, "extended.message.lsi_order_instruction", E_MESSAGE_LSI_ORDER_INSTRUCTION)?;
+    create_event(handle, "extended.message.lsi_rules_instruction", E_MESSAGE_LSI_RULES_INSTRUCTION)?;
+
+    // Physics options
+    create_prop(handle, "extended.physics.traction_control", P_PHYSICS_TRACTION_CONTROL, Property::Int(0))?;
+    create_prop(handle, "extended.physics.anti_lock_brakes", P_PHYSICS_ANTI_LOCK_BRAKES, Property::Int(0))?;
+    create_prop(handle, "extended.physics.stability_control", P_PHYSICS_STABILITY_CONTROL, Property::Int(0))?;
+    create_prop(handle, "extended.physics.auto_shift", P_PHYSICS_AUTO_SHIFT, Property::Int(0))?;
+    create_prop(handle, "extended.physics.auto_clutch", P_PHYSICS_AUTO_CLUTCH, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.invulnerable", P_PHYSICS_INVULNERABLE, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.opposite_lock", P_PHYSICS_OPPOSITE_LOCK, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.steering_help", P_PHYSICS_STEERING_HELP, Property::Int(0))?;
+    create_prop(handle, "extended.physics.braking_help", P_PHYSICS_BRAKING_HELP, Property::Int(0))?;
+    create_prop(handle, "extended.physics.spin_recovery", P_PHYSICS_SPIN_RECOVERY, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.auto_pit", P_PHYSICS_AUTO_PIT, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.auto_lift", P_PHYSICS_AUTO_LIFT, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.auto_blip", P_PHYSICS_AUTO_BLIP, Property::Bool(false))?;
+
+    create_prop(handle, "extended.physics.fuel_mult", P_PHYSICS_FUEL_MULT, Property::Int(0))?;
+    create_prop(handle, "extended.physics.tire_mult", P_PHYSICS_TIRE_MULT, Property::Int(0))?;
+    create_prop(handle, "extended.physics.mech_fail", P_PHYSICS_MECH_FAIL, Property::Int(0))?;
+    create_prop(handle, "extended.physics.allow_pitcrew_push", P_PHYSICS_ALLOW_PITCREW_PUSH, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.repeat_shifts", P_PHYSICS_REPEAT_SHIFTS, Property::Int(0))?;
+    create_prop(handle, "extended.physics.hold_clutch", P_PHYSICS_HOLD_CLUTCH, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.auto_reverse", P_PHYSICS_AUTO_REVERSE, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.alternate_neutral", P_PHYSICS_ALTERNATE_NEUTRAL, Property::Bool(false))?;
+    create_prop(handle, "extended.physics.ai_control", P_PHYSICS_AI_CONTROL, Property::Bool(false))?;
+
+    create_prop(handle, "extended.physics.manual_shift_override_time", P_PHYSICS_MANUAL_SHIFT_OVERRIDE_TIME, Property::Float(0.0))?;
+    create_prop(handle, "extended.physics.auto_shift_override_time", P_PHYSICS_AUTO_SHIFT_OVERRIDE_TIME, Property::Float(0.0))?;
+    create_prop(handle, "extended.physics.speed_sensitive_steering", P_PHYSICS_SPEED_SENSITIVE_STEERING, Property::Float(0.0))?;
+    create_prop(handle, "extended.physics.steer_ratio_speed", P_PHYSICS_STEER_RATIO_SPEED, Property::Float(0.0))?;
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub(super) struct ExtendedCache {
+    version: String,
+
+    status: MessageCache,
+    last_history: MessageCache,
+    displayed: MessageCache,
+    lsi_phase: MessageCache,
+    lsi_pit_state: MessageCache,
+    lsi_order_instruction: MessageCache,
+    lsi_rules_instruction: MessageCache,
+}
+
+/// Keeps the last text and tick count of a message
+#[derive(Default)]
+struct MessageCache {
+    text: String,
+    /// None until the first read, so we don't fire events for messages from before we connected
+    ticks: Option<i64>,
+}
+
+impl MessageCache {
+    fn read(&mut self, handle: &PluginHandle, slice: &[u8], ticks: i64, property: PropertyHandle, event: EventHandle) {
+        help_read_string(handle, slice, &mut self.text, property);
+
+        if let Some(previous) = self.ticks {
+            if ticks > previous {
+                handle.trigger_event(&event);
+            }
+        }
+        self.ticks = Some(ticks);
+    }
+
+    /// For messages without a tick counter, where we can only go by the text changing
+    fn read_without_ticks(&mut self, handle: &PluginHandle, slice: &[u8], property: PropertyHandle, event: EventHandle) {
+        let previous = self.text.clone();
+        help_read_string(handle, slice, &mut self.text, property);
+
+        if self.ticks.is_some() && previous != self.text && !self.text.is_empty() {
+            handle.trigger_event(&event);
+        }
+        self.ticks = Some(0);
+    }
+}
+
+pub(super) fn read_extended(handle: &PluginHandle, update: &PageExtended, cache: &mut ExtendedCache) {
+    help_read_string(handle, &update.version, &mut cache.version, P_EXTENDED_VERSION);
+    handle.update_property(P_EXTENDED_SESSION_STARTED, Property::from(update.session_started != 0));
+    handle.update_property(P_EXTENDED_PIT_SPEED_LIMIT, Property::from(update.current_pit_speed_limit));
+
+    cache.status.read(handle, &update.status_message, update.ticks_status_message_updated, P_MESSAGE_STATUS, E_MESSAGE_STATUS);
+    cache.last_history.read(handle, &update.last_history_message, update.ticks_last_history_message_updated, P_MESSAGE_LAST_HISTORY, E_MESSAGE_LAST_HISTORY);
+    cache.displayed.read_without_ticks(handle, &update.displayed_message_update_capture, P_MESSAGE_DISPLAYED, E_MESSAGE_DISPLAYED);
+    cache.lsi_phase.read(handle, &update.lsi_phase_message, update.ticks_lsi_phase_message_updated, P_MESSAGE_LSI_PHASE, E_MESSAGE_LSI_PHASE);
+    cache.lsi_pit_state.read(handle, &update.lsi_pit_state_message, update.ticks_lsi_pit_state_message_updated, P_MESSAGE_LSI_PIT_STATE, E_MESSAGE_LSI_PIT_STATE);
+    cache.lsi_order_instruction.read(handle, &update.lsi_order_instruction_message, update.ticks_lsi_order_instruction_message_updated, P_MESSAGE_LSI_ORDER_INSTRUCTION, E_MESSAGE_LSI_ORDER_INSTRUCTION);
+    cache.lsi_rules_instruction.read(handle, &update.lsi_rules_instruction_message, update.ticks_lsi_rules_instruction_message_updated, P_MESSAGE_LSI_RULES_INSTRUCTION, E_MESSAGE_LSI_RULES_INSTRUCTION);
+
+    read_physics(handle, &update.physics);
+
+    handle.update_property(P_EXTENDED_UPDATE, Property::from(update.header.version_update_begin));
+}
+
+fn read_physics(handle: &PluginHandle, physics: &PagePhysicsOptions) {
+    handle.update_property(P_PHYSICS_TRACTION_CONTROL, Property::from(physics.traction_control));
+    handle.update_property(P_PHYSICS_ANTI_LOCK_BRAKES, Property::from(physics.anti_lock_brakes));
+    handle.update_property(P_PHYSICS_STABILITY_CONTROL, Property::from(physics.stability_control));
+    handle.update_property(P_PHYSICS_AUTO_SHIFT, Property::from(physics.auto_shift));
+    handle.update_property(P_PHYSICS_AUTO_CLUTCH, Property::from(physics.auto_clutch != 0));
+    handle.update_property(P_PHYSICS_INVULNERABLE, Property::from(physics.invulnerable != 0));
+    handle.update_property(P_PHYSICS_OPPOSITE_LOCK, Property::from(physics.opposite_lock != 0));
+    handle.update_property(P_PHYSICS_STEERING_HELP, Property::from(physics.steering_help));
+    handle.update_property(P_PHYSICS_BRAKING_HELP, Property::from(physics.braking_help));
+    handle.update_property(P_PHYSICS_SPIN_RECOVERY, Property::from(physics.spin_recovery != 0));
+    handle.update_property(P_PHYSICS_AUTO_PIT, Property::from(physics.auto_pit != 0));
+    handle.update_property(P_PHYSICS_AUTO_LIFT, Property::from(physics.auto_lift != 0));
+    handle.update_property(P_PHYSICS_AUTO_BLIP, Property::from(physics.auto_blip != 0));
+
+    handle.update_property(P_PHYSICS_FUEL_MULT, Property::from(physics.fuel_mult));
+    handle.update_property(P_PHYSICS_TIRE_MULT, Property::from(physics.tire_mult));
+    handle.update_property(P_PHYSICS_MECH_FAIL, Property::from(physics.mech_fail));
+    handle.update_property(P_PHYSICS_ALLOW_PITCREW_PUSH, Property::from(physics.allow_pitcrew_push != 0));
+    handle.update_property(P_PHYSICS_REPEAT_SHIFTS, Property::from(physics.repeat_shifts));
+    handle.update_property(P_PHYSICS_HOLD_CLUTCH, Property::from(physics.hold_clutch != 0));
+    handle.update_property(P_PHYSICS_AUTO_REVERSE, Property::from(physics.auto_reverse != 0));
+    handle.update_property(P_PHYSICS_ALTERNATE_NEUTRAL, Property::from(physics.alternate_neutral != 0));
+    handle.update_property(P_PHYSICS_AI_CONTROL, Property::from(physics.ai_control != 0));
+
+    handle.update_property(P_PHYSICS_MANUAL_SHIFT_OVERRIDE_TIME, Property::from(physics.manual_shift_override_time));
+    handle.update_property(P_PHYSICS_AUTO_SHIFT_OVERRIDE_TIME, Property::from(physics.auto_shift_override_time));
+    handle.update_property(P_PHYSICS_SPEED_SENSITIVE_STEERING, Property::from(physics.speed_sensitive_steering));
+    handle.update_property(P_PHYSICS_STEER_RATIO_SPEED, Property::from(physics.steer_ratio_speed));
+}
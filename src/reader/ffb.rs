@@ -0,0 +1,113 @@
+use std::time::{Duration, Instant};
+
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::MapHolder;
+
+use super::create_prop;
+
+const P_FFB_FORCE: PropertyHandle = generate_property_handle!("rf2-reader.ffb.force");
+const P_FFB_CLIPPING_CURRENT_LAP: PropertyHandle = generate_property_handle!("rf2-reader.ffb.clipping.current_lap");
+const P_FFB_CLIPPING_LAST_LAP: PropertyHandle = generate_property_handle!("rf2-reader.ffb.clipping.last_lap");
+
+/// The game writes ForceFeedback at 400Hz
+pub(super) const FFB_SAMPLE_INTERVAL: Duration = Duration::from_micros(2500);
+/// Anything at or above this (in either direction) is clipping
+const FFB_CLIPPING_THRESHOLD: f64 = 1.0;
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    create_prop(handle, "ffb.force", P_FFB_FORCE, Property::Float(0.0))?;
+    create_prop(handle, "ffb.clipping.current_lap", P_FFB_CLIPPING_CURRENT_LAP, Property::Float(0.0))?;
+    create_prop(handle, "ffb.clipping.last_lap", P_FFB_CLIPPING_LAST_LAP, Property::Float(0.0))?;
+
+    Ok(())
+}
+
+pub(super) struct FfbState {
+    /// None while we don't sample, see set_active
+    next_sample: Option<Instant>,
+    lap: i32,
+    samples: u64,
+    clipped: u64,
+}
+
+impl Default for FfbState {
+    fn default() -> Self {
+        FfbState {
+            next_sample: None,
+            lap: -1,
+            samples: 0,
+            clipped: 0,
+        }
+    }
+}
+
+impl FfbState {
+    pub(super) fn next_sample(&self) -> Option<Instant> {
+        self.next_sample
+    }
+
+    /// Sampling only runs while the page is mounted and the properties are in use,
+    /// as it wakes the runner loop at 400Hz
+    fn set_active(&mut self, active: bool) {
+        match (active, self.next_sample) {
+            (true, None) => self.next_sample = Some(Instant::now()),
+            (false, Some(_)) => self.next_sample = None,
+            _ => ()
+        }
+    }
+
+    fn clipping_percentage(&self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.clipped as f64 / self.samples as f64 * 100.0
+        }
+    }
+}
+
+/// Samples the ForceFeedback page on it's own cadence, independent of the telemetry updates
+/// The page has no version numbers (it is a single f64 written atomically), so we go by time
+pub(super) fn sample(handle: &PluginHandle, mount: &MapHolder, in_use: bool, state: &mut FfbState) {
+    state.set_active(in_use && mount.force_feedback.is_some());
+    let (Some(page), Some(next_sample)) = (&mount.force_feedback, state.next_sample) else {
+        return;
+    };
+
+    let now = Instant::now();
+    if now < next_sample {
+        return;
+    }
+
+    // If we fell behind we skip the missed samples instead of trying to catch up
+    let mut next_sample = next_sample + FFB_SAMPLE_INTERVAL;
+    if next_sample < now {
+        next_sample = now + FFB_SAMPLE_INTERVAL;
+    }
+    state.next_sample = Some(next_sample);
+
+    let force = page.force();
+    handle.update_property(P_FFB_FORCE, Property::from(force));
+
+    state.samples += 1;
+    if force.abs() >= FFB_CLIPPING_THRESHOLD {
+        state.clipped += 1;
+    }
+    handle.update_property(P_FFB_CLIPPING_CURRENT_LAP, Property::from(state.clipping_percentage()));
+}
+
+/// Tracks the lap number from the telemetry, to reset the clipping statistics on a new lap
+pub(super) fn lap_update(handle: &PluginHandle, lap: i32, state: &mut FfbState) {
+    if lap == state.lap {
+        return;
+    }
+
+    // We don't publish the partial lap we connected in, or a lap reset by a session restart
+    if state.lap >= 0 && lap > state.lap {
+        handle.update_property(P_FFB_CLIPPING_LAST_LAP, Property::from(state.clipping_percentage()));
+    }
+
+    state.lap = lap;
+    state.samples = 0;
+    state.clipped = 0;
+}
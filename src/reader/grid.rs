@@ -0,0 +1,169 @@
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::data::{PageMultiRules, PageMultiSessionParticipant, RF2SessionType, MAX_MAPPED_VEHICLES};
+
+use super::{count_invalid, create_prop, help_read_string, runtime_prop_handle};
+
+const P_MULTI_RULES_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.update");
+const P_MULTI_RULES_SESSION_ID: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.session_id");
+const P_MULTI_RULES_SESSION_TYPE: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.session_type");
+const P_MULTI_RULES_SESSION_NAME: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.session_name");
+const P_MULTI_RULES_TRACK_TYPE: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.track_type");
+const P_MULTI_RULES_NUM_PARTICIPANTS: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.num_participants");
+const P_MULTI_RULES_NUM_QUAL_SESSIONS: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.num_qual_sessions");
+const P_MULTI_RULES_NUM_RACE_SESSIONS: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.num_race_sessions");
+const P_MULTI_RULES_MAX_LAPS: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.max_laps");
+const P_MULTI_RULES_MAX_TIME: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.max_time");
+/// How many grid slots are filled
+const P_MULTI_RULES_GRID_SIZE: PropertyHandle = generate_property_handle!("rf2-reader.multi_rules.grid_size");
+
+/// Property handles of a single grid position, generated at runtime like the standings
+struct GridSlotProperties {
+    id: PropertyHandle,
+    driver_name: PropertyHandle,
+    vehicle_name: PropertyHandle,
+    qualification_time: PropertyHandle,
+    server_scored: PropertyHandle,
+    /// There is no team in the layout, but entries of the same team share a pit box
+    pit_index: PropertyHandle,
+    garage_index: PropertyHandle,
+}
+
+impl GridSlotProperties {
+    fn new(position: usize) -> Self {
+        let gen = |name: &str| runtime_prop_handle(&format!("multi_rules.grid.{position}.{name}"));
+
+        GridSlotProperties {
+            id: gen("id"),
+            driver_name: gen("driver_name"),
+            vehicle_name: gen("vehicle_name"),
+            qualification_time: gen("qualification_time"),
+            server_scored: gen("server_scored"),
+            pit_index: gen("pit_index"),
+            garage_index: gen("garage_index"),
+        }
+    }
+}
+
+/// Default values of a grid slot, used on creation and for empty positions
+fn grid_slot_defaults(props: &GridSlotProperties) -> [(PropertyHandle, &'static str, Property); 7] {
+    [
+        (props.id, "id", Property::Int(-1)),
+        (props.driver_name, "driver_name", Property::from_string("")),
+        (props.vehicle_name, "vehicle_name", Property::from_string("")),
+        (props.qualification_time, "qualification_time", Property::Duration(0)),
+        (props.server_scored, "server_scored", Property::Bool(false)),
+        (props.pit_index, "pit_index", Property::Int(-1)),
+        (props.garage_index, "garage_index", Property::Int(-1)),
+    ]
+}
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    create_prop(handle, "multi_rules.update", P_MULTI_RULES_UPDATE, Property::Int(0))?;
+    create_prop(handle, "multi_rules.session_id", P_MULTI_RULES_SESSION_ID, Property::Int(0))?;
+    create_prop(handle, "multi_rules.session_type", P_MULTI_RULES_SESSION_TYPE, Property::from_string(""))?;
+    create_prop(handle, "multi_rules.session_name", P_MULTI_RULES_SESSION_NAME, Property::from_string(""))?;
+    create_prop(handle, "multi_rules.track_type", P_MULTI_RULES_TRACK_TYPE, Property::from_string(""))?;
+    create_prop(handle, "multi_rules.num_participants", P_MULTI_RULES_NUM_PARTICIPANTS, Property::Int(0))?;
+    create_prop(handle, "multi_rules.num_qual_sessions", P_MULTI_RULES_NUM_QUAL_SESSIONS, Property::Int(0))?;
+    create_prop(handle, "multi_rules.num_race_sessions", P_MULTI_RULES_NUM_RACE_SESSIONS, Property::Int(0))?;
+    create_prop(handle, "multi_rules.max_laps", P_MULTI_RULES_MAX_LAPS, Property::Int(0))?;
+    create_prop(handle, "multi_rules.max_time", P_MULTI_RULES_MAX_TIME, Property::Duration(0))?;
+    create_prop(handle, "multi_rules.grid_size", P_MULTI_RULES_GRID_SIZE, Property::Int(0))?;
+
+    for position in 1..=MAX_MAPPED_VEHICLES {
+        let props = GridSlotProperties::new(position);
+
+        for (prop_handle, name, init) in grid_slot_defaults(&props) {
+            create_prop(handle, &format!("multi_rules.grid.{position}.{name}"), prop_handle, init)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct GridSlotCache {
+    driver_name: String,
+    vehicle_name: String,
+}
+
+pub(super) struct GridState {
+    slots: Vec<(GridSlotProperties, GridSlotCache)>,
+    filled: usize,
+
+    session_name: String,
+    track_type: String,
+}
+
+impl Default for GridState {
+    fn default() -> Self {
+        GridState {
+            slots: (1..=MAX_MAPPED_VEHICLES).map(|position| (GridSlotProperties::new(position), GridSlotCache::default())).collect(),
+            filled: 0,
+
+            session_name: String::new(),
+            track_type: String::new(),
+        }
+    }
+}
+
+/// MultiRules is only written on session transitions, so this runs once per session
+pub(super) fn read_multi_rules(handle: &PluginHandle, update: &PageMultiRules, state: &mut GridState) {
+    let rules = update.multi_session_rules;
+
+    let session = count_invalid(handle, RF2SessionType::from_raw(rules.session));
+    handle.update_property(P_MULTI_RULES_SESSION_ID, Property::from(rules.session));
+    handle.update_property(P_MULTI_RULES_SESSION_TYPE, Property::from_string(session.map(|s| s.name()).unwrap_or("Unknown")));
+    help_read_string(handle, &rules.name, &mut state.session_name, P_MULTI_RULES_SESSION_NAME);
+    help_read_string(handle, &rules.track_type, &mut state.track_type, P_MULTI_RULES_TRACK_TYPE);
+    handle.update_property(P_MULTI_RULES_NUM_PARTICIPANTS, Property::from(rules.num_participants));
+    handle.update_property(P_MULTI_RULES_NUM_QUAL_SESSIONS, Property::from(rules.num_qual_sessions));
+    handle.update_property(P_MULTI_RULES_NUM_RACE_SESSIONS, Property::from(rules.num_race_sessions));
+    handle.update_property(P_MULTI_RULES_MAX_LAPS, Property::from(rules.max_laps));
+    handle.update_property(P_MULTI_RULES_MAX_TIME, Property::from_sec(rules.max_seconds.max(0) as f64));
+
+    let num_participants = if rules.num_participants >= 0 && (rules.num_participants as usize) <= MAX_MAPPED_VEHICLES {
+        rules.num_participants as usize
+    } else {
+        MAX_MAPPED_VEHICLES
+    };
+
+    // Grid position is 1-based, everyone not allowed on the grid (-1) is left out
+    let mut grid: Vec<PageMultiSessionParticipant> = update.participants[..num_participants].iter()
+        .filter(|part| part.grid_position > 0)
+        .copied()
+        .collect();
+    grid.sort_by_key(|part| part.grid_position);
+
+    for (part, (props, cache)) in grid.iter().zip(state.slots.iter_mut()) {
+        read_grid_slot(handle, part, props, cache);
+    }
+
+    for (props, cache) in state.slots.iter_mut().take(state.filled).skip(grid.len()) {
+        for (prop_handle, _, value) in grid_slot_defaults(props) {
+            handle.update_property(prop_handle, value);
+        }
+        *cache = GridSlotCache::default();
+    }
+    state.filled = grid.len();
+    handle.update_property(P_MULTI_RULES_GRID_SIZE, Property::from(grid.len() as i64));
+
+    let version = update.header.version_update_begin;
+    handle.update_property(P_MULTI_RULES_UPDATE, Property::from(version));
+}
+
+fn read_grid_slot(handle: &PluginHandle, part: &PageMultiSessionParticipant, props: &GridSlotProperties, cache: &mut GridSlotCache) {
+    handle.update_property(props.id, Property::from(part.id));
+    help_read_string(handle, &part.driver_name, &mut cache.driver_name, props.driver_name);
+    help_read_string(handle, &part.vehicle_name, &mut cache.vehicle_name, props.vehicle_name);
+
+    // Best over all qualifying sessions, unset times are 0 or negative
+    let qualification_time = part.qualification_time;
+    let best = qualification_time.iter().copied().filter(|time| *time > 0.0).fold(f32::INFINITY, f32::min);
+    handle.update_property(props.qualification_time, Property::from_sec(if best.is_finite() { best as f64 } else { 0.0 }));
+
+    handle.update_property(props.server_scored, Property::from(part.server_scored != 0));
+    handle.update_property(props.pit_index, Property::from(part.pit_index));
+    handle.update_property(props.garage_index, Property::from(part.garage_index));
+}
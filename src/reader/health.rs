@@ -0,0 +1,122 @@
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::share::{Preflight, GAMES};
+
+use super::create_prop;
+
+/// Shared setup checks passed, and at least one game is fully set up
+const P_HEALTH_OK: PropertyHandle = generate_property_handle!("rf2-reader.health.ok");
+
+/// Properties of a single setup check, in the order of Preflight::checks
+struct CheckProperties {
+    ok: PropertyHandle,
+    /// How to fix it, empty if ok
+    hint: PropertyHandle,
+}
+
+/// Checks shared by all games, in the order of Preflight::checks
+const P_HEALTH_CHECKS: [CheckProperties; 2] = [
+    CheckProperties {
+        ok: generate_property_handle!("rf2-reader.health.protontricks.ok"),
+        hint: generate_property_handle!("rf2-reader.health.protontricks.hint"),
+    },
+    CheckProperties {
+        ok: generate_property_handle!("rf2-reader.health.steam.ok"),
+        hint: generate_property_handle!("rf2-reader.health.steam.hint"),
+    },
+];
+
+/// Properties of the checks of a single game
+struct GameProperties {
+    /// All checks of this game passed
+    ok: PropertyHandle,
+    /// In the order of GameChecks::checks
+    checks: [CheckProperties; 4],
+}
+
+/// In the order of GAMES
+const P_HEALTH_GAMES: [GameProperties; 2] = [
+    GameProperties {
+        ok: generate_property_handle!("rf2-reader.health.rf2.ok"),
+        checks: [
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.rf2.prefix.ok"),
+                hint: generate_property_handle!("rf2-reader.health.rf2.prefix.hint"),
+            },
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.rf2.bridge.ok"),
+                hint: generate_property_handle!("rf2-reader.health.rf2.bridge.hint"),
+            },
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.rf2.plugin_dll.ok"),
+                hint: generate_property_handle!("rf2-reader.health.rf2.plugin_dll.hint"),
+            },
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.rf2.plugin_enabled.ok"),
+                hint: generate_property_handle!("rf2-reader.health.rf2.plugin_enabled.hint"),
+            },
+        ],
+    },
+    GameProperties {
+        ok: generate_property_handle!("rf2-reader.health.lmu.ok"),
+        checks: [
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.lmu.prefix.ok"),
+                hint: generate_property_handle!("rf2-reader.health.lmu.prefix.hint"),
+            },
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.lmu.bridge.ok"),
+                hint: generate_property_handle!("rf2-reader.health.lmu.bridge.hint"),
+            },
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.lmu.plugin_dll.ok"),
+                hint: generate_property_handle!("rf2-reader.health.lmu.plugin_dll.hint"),
+            },
+            CheckProperties {
+                ok: generate_property_handle!("rf2-reader.health.lmu.plugin_enabled.ok"),
+                hint: generate_property_handle!("rf2-reader.health.lmu.plugin_enabled.hint"),
+            },
+        ],
+    },
+];
+const _: () = assert!(P_HEALTH_GAMES.len() == GAMES.len(), "every game needs its health properties");
+
+/// Creates the health properties, already with the results of the preflight
+pub(super) fn init_properties(handle: &PluginHandle, preflight: &Preflight) -> Result<(), String> {
+    create_prop(handle, "health.ok", P_HEALTH_OK, Property::Bool(preflight.all_ok()))?;
+
+    for ((name, res), props) in preflight.checks().iter().zip(P_HEALTH_CHECKS.iter()) {
+        create_prop(handle, &format!("health.{name}.ok"), props.ok, Property::Bool(res.is_ok()))?;
+        create_prop(handle, &format!("health.{name}.hint"), props.hint, Property::from_string(res.err().unwrap_or("")))?;
+    }
+
+    for ((game, checks), props) in GAMES.iter().zip(preflight.games.iter()).zip(P_HEALTH_GAMES.iter()) {
+        create_prop(handle, &format!("health.{}.ok", game.key), props.ok, Property::Bool(checks.all_ok()))?;
+
+        for ((name, res), check) in checks.checks().iter().zip(props.checks.iter()) {
+            create_prop(handle, &format!("health.{}.{name}.ok", game.key), check.ok, Property::Bool(res.is_ok()))?;
+            create_prop(handle, &format!("health.{}.{name}.hint", game.key), check.hint, Property::from_string(res.err().unwrap_or("")))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Publishes the setup checks again, after check_setup ran them for the running game
+pub(super) fn update_properties(handle: &PluginHandle, preflight: &Preflight) {
+    handle.update_property(P_HEALTH_OK, Property::Bool(preflight.all_ok()));
+
+    for ((_, res), props) in preflight.checks().iter().zip(P_HEALTH_CHECKS.iter()) {
+        handle.update_property(props.ok, Property::Bool(res.is_ok()));
+        handle.update_property(props.hint, Property::from_string(res.err().unwrap_or("")));
+    }
+
+    for (checks, props) in preflight.games.iter().zip(P_HEALTH_GAMES.iter()) {
+        handle.update_property(props.ok, Property::Bool(checks.all_ok()));
+
+        for ((_, res), check) in checks.checks().iter().zip(props.checks.iter()) {
+            handle.update_property(check.ok, Property::Bool(res.is_ok()));
+            handle.update_property(check.hint, Property::from_string(res.err().unwrap_or("")));
+        }
+    }
+}
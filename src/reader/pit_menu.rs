@@ -0,0 +1,56 @@
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::data::PagePitMenu;
+
+use super::{create_prop, help_read_string};
+
+const P_PIT_MENU_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.update");
+const P_PIT_MENU_CATEGORY_INDEX: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.category_index");
+const P_PIT_MENU_CATEGORY: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.category");
+const P_PIT_MENU_CHOICE_INDEX: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.choice_index");
+const P_PIT_MENU_CHOICE: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.choice");
+const P_PIT_MENU_NUM_CHOICES: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.num_choices");
+/// Category and choice combined, the way the in game menu shows it (e.g. "FUEL: +42 L")
+const P_PIT_MENU_DISPLAY: PropertyHandle = generate_property_handle!("rf2-reader.pit_menu.display");
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    create_prop(handle, "pit_menu.update", P_PIT_MENU_UPDATE, Property::Int(0))?;
+    create_prop(handle, "pit_menu.category_index", P_PIT_MENU_CATEGORY_INDEX, Property::Int(0))?;
+    create_prop(handle, "pit_menu.category", P_PIT_MENU_CATEGORY, Property::from_string(""))?;
+    create_prop(handle, "pit_menu.choice_index", P_PIT_MENU_CHOICE_INDEX, Property::Int(0))?;
+    create_prop(handle, "pit_menu.choice", P_PIT_MENU_CHOICE, Property::from_string(""))?;
+    create_prop(handle, "pit_menu.num_choices", P_PIT_MENU_NUM_CHOICES, Property::Int(0))?;
+    create_prop(handle, "pit_menu.display", P_PIT_MENU_DISPLAY, Property::from_string(""))?;
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub(super) struct PitMenuCache {
+    category: String,
+    choice: String,
+    display: String,
+}
+
+pub(super) fn read_pit_menu(handle: &PluginHandle, menu: &PagePitMenu, version: u32, cache: &mut PitMenuCache) {
+    handle.update_property(P_PIT_MENU_CATEGORY_INDEX, Property::from(menu.category_index));
+    handle.update_property(P_PIT_MENU_CHOICE_INDEX, Property::from(menu.choice_index));
+    handle.update_property(P_PIT_MENU_NUM_CHOICES, Property::from(menu.num_choices));
+
+    help_read_string(handle, &menu.category_name, &mut cache.category, P_PIT_MENU_CATEGORY);
+    help_read_string(handle, &menu.choice_string, &mut cache.choice, P_PIT_MENU_CHOICE);
+
+    // Some categories already come with the colon, so we strip it to not end up with two
+    let category = cache.category.trim().trim_end_matches(':');
+    let display = if category.is_empty() {
+        cache.choice.trim().to_string()
+    } else {
+        format!("{category}: {}", cache.choice.trim())
+    };
+    if display != cache.display {
+        handle.update_property(P_PIT_MENU_DISPLAY, Property::from_string(&display));
+        cache.display = display;
+    }
+
+    handle.update_property(P_PIT_MENU_UPDATE, Property::from(version));
+}
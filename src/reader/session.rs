@@ -0,0 +1,106 @@
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::data::{PageScoringInfo, RF2GamePhase, RF2SessionType, RF2YellowFlagState};
+
+use super::{count_invalid, create_prop, help_read_string};
+
+const P_SESSION_ID: PropertyHandle = generate_property_handle!("rf2-reader.session.id");
+const P_SESSION_TYPE: PropertyHandle = generate_property_handle!("rf2-reader.session.type");
+const P_SESSION_NUMBER: PropertyHandle = generate_property_handle!("rf2-reader.session.number");
+const P_SESSION_TRACK_NAME: PropertyHandle = generate_property_handle!("rf2-reader.session.track_name");
+
+const P_SESSION_CURRENT_TIME: PropertyHandle = generate_property_handle!("rf2-reader.session.current_time");
+const P_SESSION_END_TIME: PropertyHandle = generate_property_handle!("rf2-reader.session.end_time");
+const P_SESSION_REMAINING_TIME: PropertyHandle = generate_property_handle!("rf2-reader.session.remaining_time");
+const P_SESSION_MAX_LAPS: PropertyHandle = generate_property_handle!("rf2-reader.session.max_laps");
+const P_SESSION_LAP_DISTANCE: PropertyHandle = generate_property_handle!("rf2-reader.session.lap_distance");
+const P_SESSION_NUM_VEHICLES: PropertyHandle = generate_property_handle!("rf2-reader.session.num_vehicles");
+
+const P_SESSION_GAME_PHASE_ID: PropertyHandle = generate_property_handle!("rf2-reader.session.game_phase_id");
+const P_SESSION_GAME_PHASE: PropertyHandle = generate_property_handle!("rf2-reader.session.game_phase");
+const P_SESSION_YELLOW_FLAG_STATE_ID: PropertyHandle = generate_property_handle!("rf2-reader.session.yellow_flag_state_id");
+const P_SESSION_YELLOW_FLAG_STATE: PropertyHandle = generate_property_handle!("rf2-reader.session.yellow_flag_state");
+const P_SESSION_SECTOR1_YELLOW: PropertyHandle = generate_property_handle!("rf2-reader.session.sector1_yellow");
+const P_SESSION_SECTOR2_YELLOW: PropertyHandle = generate_property_handle!("rf2-reader.session.sector2_yellow");
+const P_SESSION_SECTOR3_YELLOW: PropertyHandle = generate_property_handle!("rf2-reader.session.sector3_yellow");
+const P_SESSION_START_LIGHT: PropertyHandle = generate_property_handle!("rf2-reader.session.start_light");
+const P_SESSION_NUM_RED_LIGHTS: PropertyHandle = generate_property_handle!("rf2-reader.session.num_red_lights");
+const P_SESSION_IN_REALTIME: PropertyHandle = generate_property_handle!("rf2-reader.session.in_realtime");
+
+const P_SESSION_PLAYER_NAME: PropertyHandle = generate_property_handle!("rf2-reader.session.player_name");
+const P_SESSION_SERVER_NAME: PropertyHandle = generate_property_handle!("rf2-reader.session.server_name");
+const P_SESSION_MAX_PLAYERS: PropertyHandle = generate_property_handle!("rf2-reader.session.max_players");
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    create_prop(handle, "session.id", P_SESSION_ID, Property::Int(0))?;
+    create_prop(handle, "session.type", P_SESSION_TYPE, Property::from_string(""))?;
+    create_prop(handle, "session.number", P_SESSION_NUMBER, Property::Int(0))?;
+    create_prop(handle, "session.track_name", P_SESSION_TRACK_NAME, Property::from_string(""))?;
+
+    create_prop(handle, "session.current_time", P_SESSION_CURRENT_TIME, Property::Duration(0))?;
+    create_prop(handle, "session.end_time", P_SESSION_END_TIME, Property::Duration(0))?;
+    create_prop(handle, "session.remaining_time", P_SESSION_REMAINING_TIME, Property::Duration(0))?;
+    create_prop(handle, "session.max_laps", P_SESSION_MAX_LAPS, Property::Int(0))?;
+    create_prop(handle, "session.lap_distance", P_SESSION_LAP_DISTANCE, Property::Float(0.0))?;
+    create_prop(handle, "session.num_vehicles", P_SESSION_NUM_VEHICLES, Property::Int(0))?;
+
+    create_prop(handle, "session.game_phase_id", P_SESSION_GAME_PHASE_ID, Property::Int(0))?;
+    create_prop(handle, "session.game_phase", P_SESSION_GAME_PHASE, Property::from_string(""))?;
+    create_prop(handle, "session.yellow_flag_state_id", P_SESSION_YELLOW_FLAG_STATE_ID, Property::Int(0))?;
+    create_prop(handle, "session.yellow_flag_state", P_SESSION_YELLOW_FLAG_STATE, Property::from_string(""))?;
+    create_prop(handle, "session.sector1_yellow", P_SESSION_SECTOR1_YELLOW, Property::Bool(false))?;
+    create_prop(handle, "session.sector2_yellow", P_SESSION_SECTOR2_YELLOW, Property::Bool(false))?;
+    create_prop(handle, "session.sector3_yellow", P_SESSION_SECTOR3_YELLOW, Property::Bool(false))?;
+    create_prop(handle, "session.start_light", P_SESSION_START_LIGHT, Property::Int(0))?;
+    create_prop(handle, "session.num_red_lights", P_SESSION_NUM_RED_LIGHTS, Property::Int(0))?;
+    create_prop(handle, "session.in_realtime", P_SESSION_IN_REALTIME, Property::Bool(false))?;
+
+    create_prop(handle, "session.player_name", P_SESSION_PLAYER_NAME, Property::from_string(""))?;
+    create_prop(handle, "session.server_name", P_SESSION_SERVER_NAME, Property::from_string(""))?;
+    create_prop(handle, "session.max_players", P_SESSION_MAX_PLAYERS, Property::Int(0))?;
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub(super) struct SessionCache {
+    track_name: String,
+    player_name: String,
+    server_name: String,
+}
+
+pub(super) fn read_session(handle: &PluginHandle, info: &PageScoringInfo, cache: &mut SessionCache) {
+    let session = count_invalid(handle, RF2SessionType::from_raw(info.session));
+    handle.update_property(P_SESSION_ID, Property::from(info.session));
+    handle.update_property(P_SESSION_TYPE, Property::from_string(session.map(|s| s.name()).unwrap_or("Unknown")));
+    handle.update_property(P_SESSION_NUMBER, Property::from(session.map(|s| s.number()).unwrap_or(0)));
+    help_read_string(handle, &info.track_name, &mut cache.track_name, P_SESSION_TRACK_NAME);
+
+    let current_et = info.current_et.max(0.0);
+    let end_et = info.end_et.max(0.0);
+    handle.update_property(P_SESSION_CURRENT_TIME, Property::from_sec(current_et));
+    handle.update_property(P_SESSION_END_TIME, Property::from_sec(end_et));
+    handle.update_property(P_SESSION_REMAINING_TIME, Property::from_sec((end_et - current_et).max(0.0)));
+    handle.update_property(P_SESSION_MAX_LAPS, Property::from(info.max_laps));
+    handle.update_property(P_SESSION_LAP_DISTANCE, Property::from(info.lap_dist));
+    handle.update_property(P_SESSION_NUM_VEHICLES, Property::from(info.num_vehicles));
+
+    handle.update_property(P_SESSION_GAME_PHASE_ID, Property::from(info.game_phase));
+    handle.update_property(P_SESSION_GAME_PHASE, Property::from_string(count_invalid(handle, RF2GamePhase::from_raw(info.game_phase)).map(|p| p.name()).unwrap_or("Unknown")));
+    handle.update_property(P_SESSION_YELLOW_FLAG_STATE_ID, Property::from(info.yellow_flag_state));
+    handle.update_property(P_SESSION_YELLOW_FLAG_STATE, Property::from_string(count_invalid(handle, RF2YellowFlagState::from_raw(info.yellow_flag_state)).map(|y| y.name()).unwrap_or("Unknown")));
+
+    // Local yellows are flagged with the Pending yellow state
+    let sector_flag = info.sector_flag;
+    let pending = RF2YellowFlagState::Pending as i8;
+    handle.update_property(P_SESSION_SECTOR1_YELLOW, Property::from(sector_flag[0] == pending));
+    handle.update_property(P_SESSION_SECTOR2_YELLOW, Property::from(sector_flag[1] == pending));
+    handle.update_property(P_SESSION_SECTOR3_YELLOW, Property::from(sector_flag[2] == pending));
+    handle.update_property(P_SESSION_START_LIGHT, Property::from(info.start_light));
+    handle.update_property(P_SESSION_NUM_RED_LIGHTS, Property::from(info.num_red_lights));
+    handle.update_property(P_SESSION_IN_REALTIME, Property::from(info.in_realtime != 0));
+
+    help_read_string(handle, &info.player_name, &mut cache.player_name, P_SESSION_PLAYER_NAME);
+    help_read_string(handle, &info.server_name, &mut cache.server_name, P_SESSION_SERVER_NAME);
+    handle.update_property(P_SESSION_MAX_PLAYERS, Property::from(info.max_players));
+}
@@ -0,0 +1,213 @@
+use datarace_plugin_api::wrappers::{PluginHandle, Property, PropertyHandle};
+
+use crate::data::{PageScoring, PageVehicleScoring, MAX_MAPPED_VEHICLES};
+
+use super::{create_prop, help_read_string, runtime_prop_handle};
+
+/// Property handles of a single place in the standings
+/// These are generated at runtime, as we need one set per possible vehicle
+struct SlotProperties {
+    id: PropertyHandle,
+    is_player: PropertyHandle,
+    driver_name: PropertyHandle,
+    vehicle_name: PropertyHandle,
+    vehicle_class: PropertyHandle,
+    laps: PropertyHandle,
+
+    best_lap: PropertyHandle,
+    last_lap: PropertyHandle,
+    best_sector1: PropertyHandle,
+    best_sector2: PropertyHandle,
+    best_sector3: PropertyHandle,
+    last_sector1: PropertyHandle,
+    last_sector2: PropertyHandle,
+    last_sector3: PropertyHandle,
+
+    gap_leader: PropertyHandle,
+    laps_behind_leader: PropertyHandle,
+    gap_ahead: PropertyHandle,
+    laps_behind_ahead: PropertyHandle,
+
+    pitstops: PropertyHandle,
+    penalties: PropertyHandle,
+    in_pits: PropertyHandle,
+    finish_status: PropertyHandle,
+}
+
+impl SlotProperties {
+    fn new(place: usize) -> Self {
+        let gen = |name: &str| runtime_prop_handle(&format!("scoring.standings.{place}.{name}"));
+
+        SlotProperties {
+            id: gen("id"),
+            is_player: gen("is_player"),
+            driver_name: gen("driver_name"),
+            vehicle_name: gen("vehicle_name"),
+            vehicle_class: gen("vehicle_class"),
+            laps: gen("laps"),
+
+            best_lap: gen("best_lap"),
+            last_lap: gen("last_lap"),
+            best_sector1: gen("best_sector1"),
+            best_sector2: gen("best_sector2"),
+            best_sector3: gen("best_sector3"),
+            last_sector1: gen("last_sector1"),
+            last_sector2: gen("last_sector2"),
+            last_sector3: gen("last_sector3"),
+
+            gap_leader: gen("gap_leader"),
+            laps_behind_leader: gen("laps_behind_leader"),
+            gap_ahead: gen("gap_ahead"),
+            laps_behind_ahead: gen("laps_behind_ahead"),
+
+            pitstops: gen("pitstops"),
+            penalties: gen("penalties"),
+            in_pits: gen("in_pits"),
+            finish_status: gen("finish_status"),
+        }
+    }
+}
+
+/// Default values of a slot, used on creation and when a car leaves
+fn slot_defaults(props: &SlotProperties) -> [(PropertyHandle, &'static str, Property); 22] {
+    [
+        (props.id, "id", Property::Int(-1)),
+        (props.is_player, "is_player", Property::Bool(false)),
+        (props.driver_name, "driver_name", Property::from_string("")),
+        (props.vehicle_name, "vehicle_name", Property::from_string("")),
+        (props.vehicle_class, "vehicle_class", Property::from_string("")),
+        (props.laps, "laps", Property::Int(0)),
+
+        (props.best_lap, "best_lap", Property::Duration(0)),
+        (props.last_lap, "last_lap", Property::Duration(0)),
+        (props.best_sector1, "best_sector1", Property::Duration(0)),
+        (props.best_sector2, "best_sector2", Property::Duration(0)),
+        (props.best_sector3, "best_sector3", Property::Duration(0)),
+        (props.last_sector1, "last_sector1", Property::Duration(0)),
+        (props.last_sector2, "last_sector2", Property::Duration(0)),
+        (props.last_sector3, "last_sector3", Property::Duration(0)),
+
+        (props.gap_leader, "gap_leader", Property::Float(0.0)),
+        (props.laps_behind_leader, "laps_behind_leader", Property::Int(0)),
+        (props.gap_ahead, "gap_ahead", Property::Float(0.0)),
+        (props.laps_behind_ahead, "laps_behind_ahead", Property::Int(0)),
+
+        (props.pitstops, "pitstops", Property::Int(0)),
+        (props.penalties, "penalties", Property::Int(0)),
+        (props.in_pits, "in_pits", Property::Bool(false)),
+        (props.finish_status, "finish_status", Property::from_string("")),
+    ]
+}
+
+/// Creates the properties for all standing slots
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    for place in 1..=MAX_MAPPED_VEHICLES {
+        let props = SlotProperties::new(place);
+
+        for (prop_handle, name, init) in slot_defaults(&props) {
+            create_prop(handle, &format!("scoring.standings.{place}.{name}"), prop_handle, init)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct SlotCache {
+    driver_name: String,
+    vehicle_name: String,
+    vehicle_class: String,
+}
+
+pub(super) struct StandingsState {
+    slots: Vec<(SlotProperties, SlotCache)>,
+    /// How many slots were filled during the last update, so we know which to clear
+    filled: usize,
+}
+
+impl Default for StandingsState {
+    fn default() -> Self {
+        StandingsState {
+            slots: (1..=MAX_MAPPED_VEHICLES).map(|place| (SlotProperties::new(place), SlotCache::default())).collect(),
+            filled: 0,
+        }
+    }
+}
+
+/// Publishes the field sorted by place
+pub(super) fn read_standings(handle: &PluginHandle, update: &PageScoring, num_vehicles: usize, state: &mut StandingsState) {
+    let mut order: Vec<PageVehicleScoring> = update.vehicles[..num_vehicles].to_vec();
+    // place is 1-based, 0 is therefore invalid and goes to the back
+    order.sort_by_key(|veh| if veh.place == 0 { u8::MAX as u16 + 1 } else { veh.place as u16 });
+
+    for (veh, (props, cache)) in order.iter().zip(state.slots.iter_mut()) {
+        read_slot(handle, veh, props, cache);
+    }
+
+    // Clearing the slots of cars that left
+    for (props, cache) in state.slots.iter_mut().take(state.filled).skip(num_vehicles) {
+        for (prop_handle, _, value) in slot_defaults(props) {
+            handle.update_property(prop_handle, value);
+        }
+        *cache = SlotCache::default();
+    }
+    state.filled = num_vehicles;
+}
+
+fn read_slot(handle: &PluginHandle, veh: &PageVehicleScoring, props: &SlotProperties, cache: &mut SlotCache) {
+    handle.update_property(props.id, Property::from(veh.id));
+    handle.update_property(props.is_player, Property::from(veh.is_player != 0));
+    help_read_string(handle, &veh.driver_name, &mut cache.driver_name, props.driver_name);
+    help_read_string(handle, &veh.vehicle_name, &mut cache.vehicle_name, props.vehicle_name);
+    help_read_string(handle, &veh.vehicle_class, &mut cache.vehicle_class, props.vehicle_class);
+    handle.update_property(props.laps, Property::from(veh.total_laps));
+
+    // Sector 2 is given including sector 1, so we have to split them.
+    // The best sectors are the splits of the best lap, the best_sector fields are the best of each
+    // sector over all laps, so mixing them with the best lap gives times of no real lap
+    let best_lap_sector1 = veh.best_lap_sector1 as f64;
+    let best_lap_sector2 = veh.best_lap_sector2 as f64;
+    handle.update_property(props.best_lap, lap_time(veh.best_lap_time));
+    handle.update_property(props.last_lap, lap_time(veh.last_lap_time));
+    handle.update_property(props.best_sector1, lap_time(best_lap_sector1));
+    handle.update_property(props.best_sector2, split_time(best_lap_sector2, best_lap_sector1));
+    handle.update_property(props.best_sector3, split_time(veh.best_lap_time, best_lap_sector2));
+    handle.update_property(props.last_sector1, lap_time(veh.last_sector1));
+    handle.update_property(props.last_sector2, split_time(veh.last_sector2, veh.last_sector1));
+    handle.update_property(props.last_sector3, split_time(veh.last_lap_time, veh.last_sector2));
+
+    handle.update_property(props.gap_leader, Property::from(veh.time_behind_leader));
+    handle.update_property(props.laps_behind_leader, Property::from(veh.laps_behind_leader));
+    handle.update_property(props.gap_ahead, Property::from(veh.time_behind_next));
+    handle.update_property(props.laps_behind_ahead, Property::from(veh.laps_behind_next));
+
+    handle.update_property(props.pitstops, Property::from(veh.num_pitstops));
+    handle.update_property(props.penalties, Property::from(veh.num_penalties));
+    handle.update_property(props.in_pits, Property::from(veh.in_pits != 0));
+    handle.update_property(props.finish_status, Property::from_string(finish_status_name(veh.finish_status)));
+}
+
+/// rF2 uses negative values for times that are not set (yet)
+#[inline]
+fn lap_time(time: f64) -> Property {
+    Property::from_sec(if time > 0.0 { time } else { 0.0 })
+}
+
+#[inline]
+fn split_time(until: f64, previous: f64) -> Property {
+    if until > 0.0 && previous > 0.0 {
+        lap_time(until - previous)
+    } else {
+        lap_time(0.0)
+    }
+}
+
+fn finish_status_name(status: i8) -> &'static str {
+    match status {
+        0 => "None",
+        1 => "Finished",
+        2 => "DNF",
+        3 => "DQ",
+        _ => "Unknown"
+    }
+}
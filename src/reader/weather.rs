@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+
+use datarace_plugin_api::{macros::generate_property_handle, wrappers::{PluginHandle, Property, PropertyHandle}};
+
+use crate::data::{PageScoringInfo, PageWeather};
+
+use super::{create_prop, KELVIN_OFFSET};
+
+const P_WEATHER_DARK_CLOUD: PropertyHandle = generate_property_handle!("rf2-reader.weather.dark_cloud");
+const P_WEATHER_RAINING: PropertyHandle = generate_property_handle!("rf2-reader.weather.raining");
+const P_WEATHER_AMBIENT_TEMP: PropertyHandle = generate_property_handle!("rf2-reader.weather.ambient_temp");
+const P_WEATHER_TRACK_TEMP: PropertyHandle = generate_property_handle!("rf2-reader.weather.track_temp");
+
+const P_WEATHER_WIND_X: PropertyHandle = generate_property_handle!("rf2-reader.weather.wind_x");
+const P_WEATHER_WIND_Y: PropertyHandle = generate_property_handle!("rf2-reader.weather.wind_y");
+const P_WEATHER_WIND_Z: PropertyHandle = generate_property_handle!("rf2-reader.weather.wind_z");
+const P_WEATHER_WIND_SPEED: PropertyHandle = generate_property_handle!("rf2-reader.weather.wind_speed");
+const P_WEATHER_WIND_DIRECTION: PropertyHandle = generate_property_handle!("rf2-reader.weather.wind_direction");
+
+const P_WEATHER_MIN_PATH_WETNESS: PropertyHandle = generate_property_handle!("rf2-reader.weather.min_path_wetness");
+const P_WEATHER_AVG_PATH_WETNESS: PropertyHandle = generate_property_handle!("rf2-reader.weather.avg_path_wetness");
+const P_WEATHER_MAX_PATH_WETNESS: PropertyHandle = generate_property_handle!("rf2-reader.weather.max_path_wetness");
+
+const P_WEATHER_RAINING_TREND: PropertyHandle = generate_property_handle!("rf2-reader.weather.raining_trend");
+const P_WEATHER_RAINING_TREND_NAME: PropertyHandle = generate_property_handle!("rf2-reader.weather.raining_trend_name");
+const P_WEATHER_WETNESS_TREND: PropertyHandle = generate_property_handle!("rf2-reader.weather.wetness_trend");
+const P_WEATHER_WETNESS_TREND_NAME: PropertyHandle = generate_property_handle!("rf2-reader.weather.wetness_trend_name");
+
+// Weather page
+const P_WEATHER_UPDATE: PropertyHandle = generate_property_handle!("rf2-reader.weather.update");
+const P_WEATHER_TRACK_NODE_SIZE: PropertyHandle = generate_property_handle!("rf2-reader.weather.track_node_size");
+const P_WEATHER_TARGET_TIME: PropertyHandle = generate_property_handle!("rf2-reader.weather.target.time");
+const P_WEATHER_TARGET_RAINING: PropertyHandle = generate_property_handle!("rf2-reader.weather.target.raining");
+const P_WEATHER_TARGET_CLOUDINESS: PropertyHandle = generate_property_handle!("rf2-reader.weather.target.cloudiness");
+const P_WEATHER_TARGET_AMBIENT_TEMP: PropertyHandle = generate_property_handle!("rf2-reader.weather.target.ambient_temp");
+const P_WEATHER_TARGET_WIND_MAX_SPEED: PropertyHandle = generate_property_handle!("rf2-reader.weather.target.wind_max_speed");
+/// Target rain for each node of the 3x3 grid around the track
+const P_WEATHER_TARGET_NODE_RAINING: [PropertyHandle; 9] = [
+    generate_property_handle!("rf2-reader.weather.target.node.0.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.1.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.2.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.3.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.4.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.5.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.6.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.7.raining"),
+    generate_property_handle!("rf2-reader.weather.target.node.8.raining"),
+];
+
+/// Timespan (in session time seconds) the trends are calculated over
+const TREND_WINDOW: f64 = 5.0 * 60.0;
+/// Scoring updates at 5Hz, we don't need that many samples for a trend over minutes
+const TREND_SAMPLE_INTERVAL: f64 = 10.0;
+/// Change per minute below which we consider the value to be steady
+const TREND_STEADY_THRESHOLD: f64 = 0.005;
+
+pub(super) fn init_properties(handle: &PluginHandle) -> Result<(), String> {
+    create_prop(handle, "weather.dark_cloud", P_WEATHER_DARK_CLOUD, Property::Float(0.0))?;
+    create_prop(handle, "weather.raining", P_WEATHER_RAINING, Property::Float(0.0))?;
+    create_prop(handle, "weather.ambient_temp", P_WEATHER_AMBIENT_TEMP, Property::Float(0.0))?;
+    create_prop(handle, "weather.track_temp", P_WEATHER_TRACK_TEMP, Property::Float(0.0))?;
+
+    create_prop(handle, "weather.wind_x", P_WEATHER_WIND_X, Property::Float(0.0))?;
+    create_prop(handle, "weather.wind_y", P_WEATHER_WIND_Y, Property::Float(0.0))?;
+    create_prop(handle, "weather.wind_z", P_WEATHER_WIND_Z, Property::Float(0.0))?;
+    create_prop(handle, "weather.wind_speed", P_WEATHER_WIND_SPEED, Property::Float(0.0))?;
+    create_prop(handle, "weather.wind_direction", P_WEATHER_WIND_DIRECTION, Property::Float(0.0))?;
+
+    create_prop(handle, "weather.min_path_wetness", P_WEATHER_MIN_PATH_WETNESS, Property::Float(0.0))?;
+    create_prop(handle, "weather.avg_path_wetness", P_WEATHER_AVG_PATH_WETNESS, Property::Float(0.0))?;
+    create_prop(handle, "weather.max_path_wetness", P_WEATHER_MAX_PATH_WETNESS, Property::Float(0.0))?;
+
+    create_prop(handle, "weather.raining_trend", P_WEATHER_RAINING_TREND, Property::Float(0.0))?;
+    create_prop(handle, "weather.raining_trend_name", P_WEATHER_RAINING_TREND_NAME, Property::from_string(Trend::STEADY))?;
+    create_prop(handle, "weather.wetness_trend", P_WEATHER_WETNESS_TREND, Property::Float(0.0))?;
+    create_prop(handle, "weather.wetness_trend_name", P_WEATHER_WETNESS_TREND_NAME, Property::from_string(Trend::STEADY))?;
+
+    create_prop(handle, "weather.update", P_WEATHER_UPDATE, Property::Int(0))?;
+    create_prop(handle, "weather.track_node_size", P_WEATHER_TRACK_NODE_SIZE, Property::Float(0.0))?;
+    create_prop(handle, "weather.target.time", P_WEATHER_TARGET_TIME, Property::Duration(0))?;
+    create_prop(handle, "weather.target.raining", P_WEATHER_TARGET_RAINING, Property::Float(0.0))?;
+    create_prop(handle, "weather.target.cloudiness", P_WEATHER_TARGET_CLOUDINESS, Property::Float(0.0))?;
+    create_prop(handle, "weather.target.ambient_temp", P_WEATHER_TARGET_AMBIENT_TEMP, Property::Float(0.0))?;
+    create_prop(handle, "weather.target.wind_max_speed", P_WEATHER_TARGET_WIND_MAX_SPEED, Property::Float(0.0))?;
+    for (i, prop) in P_WEATHER_TARGET_NODE_RAINING.iter().enumerate() {
+        create_prop(handle, &format!("weather.target.node.{i}.raining"), *prop, Property::Float(0.0))?;
+    }
+
+    Ok(())
+}
+
+/// Rolling record of a value over the session time, to figure out if it is rising or falling
+#[derive(Default)]
+struct Trend {
+    /// (session time, value)
+    samples: VecDeque<(f64, f64)>,
+}
+
+impl Trend {
+    const RISING: &'static str = "Rising";
+    const FALLING: &'static str = "Falling";
+    const STEADY: &'static str = "Steady";
+
+    fn push(&mut self, et: f64, value: f64) {
+        if let Some((last_et, _)) = self.samples.back() {
+            if et < *last_et {
+                // Session restarted (or a new one started), old samples are meaningless
+                self.samples.clear();
+            } else if et < *last_et + TREND_SAMPLE_INTERVAL {
+                return;
+            }
+        }
+
+        self.samples.push_back((et, value));
+
+        while let Some((first_et, _)) = self.samples.front() {
+            if *first_et + TREND_WINDOW < et {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Change per minute over the window
+    fn per_minute(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some((first_et, first)), Some((last_et, last))) if last_et > first_et => {
+                (last - first) / (last_et - first_et) * 60.0
+            },
+            _ => 0.0
+        }
+    }
+
+    fn name(per_minute: f64) -> &'static str {
+        if per_minute > TREND_STEADY_THRESHOLD {
+            Trend::RISING
+        } else if per_minute < -TREND_STEADY_THRESHOLD {
+            Trend::FALLING
+        } else {
+            Trend::STEADY
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct WeatherState {
+    raining: Trend,
+    wetness: Trend,
+}
+
+pub(super) fn read_weather(handle: &PluginHandle, info: &PageScoringInfo, state: &mut WeatherState) {
+    handle.update_property(P_WEATHER_DARK_CLOUD, Property::from(info.dark_cloud));
+    handle.update_property(P_WEATHER_RAINING, Property::from(info.raining));
+    handle.update_property(P_WEATHER_AMBIENT_TEMP, Property::from(info.ambient_temp));
+    handle.update_property(P_WEATHER_TRACK_TEMP, Property::from(info.track_temp));
+
+    // Wind is a vector in world coordinates (y is up),
+    // so the direction is the heading the wind blows towards on the x/z plane
+    let wind = info.wind;
+    let speed = (wind.x * wind.x + wind.y * wind.y + wind.z * wind.z).sqrt();
+    let direction = wind.x.atan2(wind.z).to_degrees().rem_euclid(360.0);
+    handle.update_property(P_WEATHER_WIND_X, Property::from(wind.x));
+    handle.update_property(P_WEATHER_WIND_Y, Property::from(wind.y));
+    handle.update_property(P_WEATHER_WIND_Z, Property::from(wind.z));
+    handle.update_property(P_WEATHER_WIND_SPEED, Property::from(speed));
+    handle.update_property(P_WEATHER_WIND_DIRECTION, Property::from(direction));
+
+    handle.update_property(P_WEATHER_MIN_PATH_WETNESS, Property::from(info.min_path_wetness));
+    handle.update_property(P_WEATHER_AVG_PATH_WETNESS, Property::from(info.avg_path_wetness));
+    handle.update_property(P_WEATHER_MAX_PATH_WETNESS, Property::from(info.max_path_wetness));
+
+    state.raining.push(info.current_et, info.raining);
+    state.wetness.push(info.current_et, info.avg_path_wetness);
+
+    let raining_trend = state.raining.per_minute();
+    let wetness_trend = state.wetness.per_minute();
+    handle.update_property(P_WEATHER_RAINING_TREND, Property::from(raining_trend));
+    handle.update_property(P_WEATHER_RAINING_TREND_NAME, Property::from_string(Trend::name(raining_trend)));
+    handle.update_property(P_WEATHER_WETNESS_TREND, Property::from(wetness_trend));
+    handle.update_property(P_WEATHER_WETNESS_TREND_NAME, Property::from_string(Trend::name(wetness_trend)));
+}
+
+/// The Weather page only holds the next target the weather is moving towards, and the session time
+/// it is reached at. We publish that as is, how the game gets there is not known
+pub(super) fn read_target(handle: &PluginHandle, update: &PageWeather) {
+    let control = update.weather_info;
+    let nodes = control.raining;
+
+    handle.update_property(P_WEATHER_TRACK_NODE_SIZE, Property::from(update.track_node_size));
+    handle.update_property(P_WEATHER_TARGET_TIME, Property::from_sec(control.et.max(0.0)));
+    // The center node is the one scoring reports as the current rain at the track
+    handle.update_property(P_WEATHER_TARGET_RAINING, Property::from(nodes[4]));
+    handle.update_property(P_WEATHER_TARGET_CLOUDINESS, Property::from(control.cloudiness));
+    handle.update_property(P_WEATHER_TARGET_AMBIENT_TEMP, Property::from(control.ambient_temp_k - KELVIN_OFFSET));
+    handle.update_property(P_WEATHER_TARGET_WIND_MAX_SPEED, Property::from(control.wind_max_speed));
+    for (rain, prop) in nodes.iter().zip(P_WEATHER_TARGET_NODE_RAINING.iter()) {
+        handle.update_property(*prop, Property::from(*rain));
+    }
+
+    let version = update.header.version_update_begin;
+    handle.update_property(P_WEATHER_UPDATE, Property::from(version));
+}
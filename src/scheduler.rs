@@ -0,0 +1,241 @@
+use std::time::{Duration, Instant};
+
+use crate::reader::ReaderState;
+
+/// Longest we sleep at once, so the update_lock handshake (and queued actions) stay responsive
+const MAX_WAIT: Duration = Duration::from_millis(20);
+/// How often we check once an update is overdue
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// A page without updates for this many nominal intervals is stalled (paused, loading),
+/// and no longer polled for
+const STALL_FACTOR: u32 = 4;
+/// Weight of a new observation in the running interval and jitter averages
+const SMOOTHING: f64 = 0.1;
+/// How many jitters (mean deviations) before the expected update we wake up
+const JITTER_MARGIN: f64 = 2.0;
+
+/// Rates the rF2SharedMemoryMap plugin writes the pages at
+const TELEMETRY_INTERVAL: Duration = Duration::from_millis(20);
+const SCORING_INTERVAL: Duration = Duration::from_millis(200);
+const RULES_INTERVAL: Duration = Duration::from_millis(333);
+const EXTENDED_INTERVAL: Duration = Duration::from_millis(200);
+const PIT_INFO_INTERVAL: Duration = Duration::from_millis(10);
+const WEATHER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Learns when a page updates from watching its version
+struct PageTimer {
+    nominal: Duration,
+    /// Average observed time between updates, in seconds
+    interval: f64,
+    /// Average deviation from the interval, in seconds
+    jitter: f64,
+    version: u32,
+    last_change: Option<Instant>,
+}
+
+impl PageTimer {
+    fn new(nominal: Duration) -> Self {
+        PageTimer {
+            nominal,
+            interval: nominal.as_secs_f64(),
+            jitter: 0.0,
+            version: 0,
+            last_change: None,
+        }
+    }
+
+    fn observe(&mut self, version: u32, now: Instant) {
+        if version == self.version {
+            return;
+        }
+        self.version = version;
+
+        if let Some(last) = self.last_change {
+            let observed = now.duration_since(last);
+
+            // Gaps from a pause or loading would throw off the averages
+            if observed < self.nominal * STALL_FACTOR {
+                let observed = observed.as_secs_f64();
+                let deviation = (observed - self.interval).abs();
+
+                self.interval += SMOOTHING * (observed - self.interval);
+                self.jitter += SMOOTHING * (deviation - self.jitter);
+            }
+        }
+
+        self.last_change = Some(now);
+    }
+
+    /// When we should next look at this page, None if it is not updating
+    fn next_wake(&self, now: Instant) -> Option<Instant> {
+        let last = self.last_change?;
+
+        let lead = (self.interval - JITTER_MARGIN * self.jitter).max(0.0);
+        let expected = last + Duration::from_secs_f64(lead);
+        if expected > now {
+            Some(expected)
+        } else if now.duration_since(last) < self.nominal * STALL_FACTOR {
+            Some(now + POLL_INTERVAL)
+        } else {
+            None
+        }
+    }
+}
+
+/// Versions of the pages read last, see ReaderState::page_versions.
+/// Pages that are not mounted stay at 0, and so are never expected to update
+pub(crate) struct PageVersions {
+    pub telemetry: u32,
+    pub scoring: u32,
+    pub rules: u32,
+    pub extended: u32,
+    pub pit_info: u32,
+    pub weather: u32,
+}
+
+/// Paces the runner loop, sleeping until the next page is expected to update
+/// instead of spinning on the version numbers
+pub(crate) struct Scheduler {
+    telemetry: PageTimer,
+    scoring: PageTimer,
+    rules: PageTimer,
+    extended: PageTimer,
+    pit_info: PageTimer,
+    weather: PageTimer,
+}
+
+impl Scheduler {
+    pub(crate) fn new() -> Self {
+        // MultiRules only changes with the session, and Graphics follows the frame rate,
+        // so neither has a rate worth waiting for
+        Scheduler {
+            telemetry: PageTimer::new(TELEMETRY_INTERVAL),
+            scoring: PageTimer::new(SCORING_INTERVAL),
+            rules: PageTimer::new(RULES_INTERVAL),
+            extended: PageTimer::new(EXTENDED_INTERVAL),
+            pit_info: PageTimer::new(PIT_INFO_INTERVAL),
+            weather: PageTimer::new(WEATHER_INTERVAL),
+        }
+    }
+
+    /// Step of the runner loop after reading, learns from what was read and sleeps until the next read is due
+    pub(crate) fn pace(&mut self, reader_state: &ReaderState) {
+        self.observe(&reader_state.page_versions());
+        self.wait(reader_state.next_deadline());
+    }
+
+    /// Records which pages updated, call after reading them.
+    /// Takes the versions we read, as a page the game wrote after our read would otherwise be
+    /// counted as read, and its update noticed late
+    fn observe(&mut self, versions: &PageVersions) {
+        let now = Instant::now();
+
+        self.telemetry.observe(versions.telemetry, now);
+        self.scoring.observe(versions.scoring, now);
+        self.rules.observe(versions.rules, now);
+        self.extended.observe(versions.extended, now);
+        self.pit_info.observe(versions.pit_info, now);
+        self.weather.observe(versions.weather, now);
+    }
+
+    /// Sleeps until the next expected page update, or the deadline if it is sooner
+    fn wait(&self, deadline: Option<Instant>) {
+        let now = Instant::now();
+
+        let wake = [&self.telemetry, &self.scoring, &self.rules, &self.extended, &self.pit_info, &self.weather]
+            .iter()
+            .filter_map(|page| page.next_wake(now))
+            .chain(deadline)
+            .chain([now + MAX_WAIT])
+            .min()
+            .unwrap_or(now);
+
+        if wake > now {
+            std::thread::sleep(wake - now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicU32, Ordering}, Arc, Mutex};
+
+    use super::*;
+    use crate::config::Config;
+
+    /// CPU time used by the calling thread
+    fn thread_cpu_time() -> Duration {
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        assert_eq!(unsafe { libc::getrusage(libc::RUSAGE_THREAD, &mut usage) }, 0);
+
+        let secs = |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+        secs(usage.ru_utime) + secs(usage.ru_stime)
+    }
+
+    /// Harness with a synthetic writer updating telemetry at its nominal rate, and the runner loop reduced to
+    /// reading the version and pacing, with the deadlines of the ReaderState like in runner_loop (ForceFeedback not mounted).
+    /// Measures the CPU the loop uses, and how long after a write it reads
+    #[test]
+    fn paces_reads_to_a_synthetic_writer() {
+        const WRITES: usize = 60;
+
+        let version = Arc::new(AtomicU32::new(0));
+        let written = Arc::new(Mutex::new(Vec::with_capacity(WRITES)));
+        let writer = {
+            let (version, written) = (version.clone(), written.clone());
+            std::thread::spawn(move || {
+                for _ in 0..WRITES {
+                    std::thread::sleep(TELEMETRY_INTERVAL);
+                    let mut written = written.lock().unwrap();
+                    version.fetch_add(1, Ordering::Release);
+                    written.push(Instant::now());
+                }
+            })
+        };
+
+        let mut scheduler = Scheduler::new();
+        let mut read = Vec::with_capacity(WRITES);
+        let mut reader_state = ReaderState::new(&Config::default());
+        let mut consumed = 0;
+        let mut wakes = 0u32;
+
+        let start = Instant::now();
+        let cpu_start = thread_cpu_time();
+        while read.len() < WRITES {
+            assert!(start.elapsed() < TELEMETRY_INTERVAL * WRITES as u32 * 4, "reader fell behind the writer");
+
+            let current = version.load(Ordering::Acquire);
+            if current != consumed {
+                // A skipped write counts as read now as well
+                let now = Instant::now();
+                read.extend((consumed..current).map(|_| now));
+                consumed = current;
+                reader_state.set_telemetry_version(current);
+            }
+
+            assert_eq!(reader_state.next_deadline(), None, "ForceFeedback is not mounted, nothing to sample");
+            scheduler.pace(&reader_state);
+            wakes += 1;
+        }
+        let cpu = thread_cpu_time() - cpu_start;
+        let wall = start.elapsed();
+        writer.join().unwrap();
+
+        let written = written.lock().unwrap();
+        let latencies: Vec<Duration> = written.iter().zip(read.iter()).map(|(w, r)| r.saturating_duration_since(*w)).collect();
+        // The first writes are read at the MAX_WAIT pace, before the rate is learned
+        let learned = &latencies[10..];
+        let mean = learned.iter().sum::<Duration>() / learned.len() as u32;
+        let max = learned.iter().max().copied().unwrap_or_default();
+        let cpu_share = cpu.as_secs_f64() / wall.as_secs_f64();
+
+        println!("{wakes} wakes for {WRITES} writes in {wall:?}, cpu {cpu:?} ({:.2}%), added latency mean {mean:?} max {max:?}", cpu_share * 100.0);
+
+        // A busy loop would use a whole core, and waking once per MAX_WAIT would add 10ms on average
+        assert!(cpu_share < 0.1, "loop used {:.2}% cpu", cpu_share * 100.0);
+        assert!(mean < Duration::from_millis(5), "mean added latency {mean:?}");
+        // At 400Hz ForceFeedback sampling this would be around 500 wakes, the learned rate and the
+        // MAX_WAIT cap before it was learned are below three per write
+        assert!((wakes as usize) < WRITES * 3, "woke {wakes} times for {WRITES} writes");
+    }
+}
@@ -0,0 +1,35 @@
+/// A game on the rF2 engine that runs the rF2SharedMemoryMap plugin.
+/// They all share the same page layouts, and are published under the same rf2-reader properties
+#[derive(Debug)]
+pub(crate) struct GameProfile {
+    /// Name published in rf2-reader.game
+    pub name: &'static str,
+    /// Short name used in the per game properties, like rf2-reader.health.rf2.ok
+    pub key: &'static str,
+    /// Steam app id, also the id of the proton prefix
+    pub app_id: u32,
+    /// Part of the cmdline of the running game process
+    pub exe_fragment: &'static str,
+    /// Where the plugin dll goes, relative to the game install
+    pub plugin_dir: &'static str,
+}
+
+pub(crate) const GAMES: [GameProfile; 2] = [
+    GameProfile {
+        name: "rFactor 2",
+        key: "rf2",
+        app_id: 365960,
+        // We find the stable entry process which cmdline looks something like this:
+        // Z:\home\Lukas\.local\share\Steam\steamapps\common\Assetto Corsa Competizione\acc.exe
+        // "rFactor 2/Launcher/Launch rFactor.exe" is only the launcher
+        exe_fragment: "rFactor 2\\Bin64\\rFactor2.exe",
+        plugin_dir: "Bin64/Plugins",
+    },
+    GameProfile {
+        name: "Le Mans Ultimate",
+        key: "lmu",
+        app_id: 2399420,
+        exe_fragment: "Le Mans Ultimate\\Le Mans Ultimate.exe",
+        plugin_dir: "Plugins",
+    },
+];
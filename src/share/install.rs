@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use datarace_plugin_api::wrappers::PluginHandle;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{config::Config, data::RF2SubscribedBuffer};
+
+use super::{check_for_program_running, GameProfile, BRIDGE_EXE_NAME};
+
+const PLUGIN_DLL_NAME: &'static str = "rFactor2SharedMemoryMapPlugin64.dll";
+const PLUGIN_CONFIG_FILE: &'static str = "UserData/player/CustomPluginVariables.json";
+const PLUGIN_CONFIG_BACKUP_FILE: &'static str = "UserData/player/CustomPluginVariables.json.bak";
+
+/// Installs the bridge from source into the prefix (target), or replaces the installed one if it differs.
+/// Without a source an already installed bridge is used as is
+pub(super) fn install_bridge(handle: &PluginHandle, source: Option<&Path>, target: &Path) -> Result<(), String> {
+    let source = match source {
+        Some(source) => source,
+        None if target.exists() => {
+            handle.log_info(format!("No bridge source configured, using installed bridge {}", target.to_string_lossy()));
+            return Ok(());
+        },
+        None => return Err(format!("{BRIDGE_EXE_NAME} is not installed and no source to install it from is configured, set DR_RF2_BRIDGE_SOURCE to the path of {BRIDGE_EXE_NAME}")),
+    };
+
+    let source_hash = file_hash(source).map_err(|e| format!("Unable to read bridge source {}: {e}", source.to_string_lossy()))?;
+
+    if target.exists() {
+        match file_hash(target) {
+            Ok(hash) if hash == source_hash => {
+                handle.log_info(format!("Installed bridge is up to date ({source_hash})"));
+                return Ok(());
+            },
+            Ok(hash) => handle.log_info(format!("Installed bridge ({hash}) differs from source ({source_hash}), replacing it")),
+            Err(e) => handle.log_error(format!("Unable to read installed bridge, replacing it: {e}")),
+        }
+
+        // The bridge keeps running while the game is, and we can't replace a running executable
+        if check_for_program_running(None, format!("DataRace\\{}", BRIDGE_EXE_NAME)).is_some() {
+            handle.log_error("Bridge is running, unable to replace it, using the installed one until the next launch");
+            return Ok(());
+        }
+    } else {
+        handle.log_info(format!("Installing bridge to {}", target.to_string_lossy()));
+    }
+
+    std::fs::copy(source, target).map_err(|e| format!("Failed to install bridge to {}: {e}", target.to_string_lossy()))?;
+
+    // Verifying the copy, as a half written bridge fails in odd ways inside proton
+    let installed_hash = file_hash(target).map_err(|e| format!("Unable to read installed bridge: {e}"))?;
+    if installed_hash != source_hash {
+        return Err(format!("Installed bridge hash {installed_hash} does not match source {source_hash}"));
+    }
+
+    handle.log_info(format!("Bridge installed ({installed_hash})"));
+    Ok(())
+}
+
+/// Sha256 of the file as hex
+fn file_hash(path: &Path) -> std::io::Result<String> {
+    let data = std::fs::read(path)?;
+    let hash = Sha256::digest(&data);
+
+    Ok(hash.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Locates the game install through steam
+pub(super) fn game_dir(game: &GameProfile) -> Option<PathBuf> {
+    steamlocate::SteamDir::locate()
+        .and_then(|mut steam| steam.app(&game.app_id).map(|app| app.path.clone()))
+}
+
+pub(super) fn plugin_dll_path(game_dir: &Path, game: &GameProfile) -> PathBuf {
+    game_dir.join(game.plugin_dir).join(PLUGIN_DLL_NAME)
+}
+
+/// Installs the rF2SharedMemoryMap plugin into the game if it is missing, and enables it in the CustomPluginVariables.json.
+/// Running this again with the same config changes nothing
+pub(super) fn install_plugin(handle: &PluginHandle, game_dir: &Path, game: &GameProfile, config: &Config) -> Result<(), String> {
+    let dll_path = plugin_dll_path(game_dir, game);
+    if !dll_path.exists() {
+        let source = config.plugin_source.as_deref().ok_or(format!("{PLUGIN_DLL_NAME} is not installed and no source to install it from is configured, set DR_RF2_PLUGIN_SOURCE to the path of {PLUGIN_DLL_NAME}"))?;
+
+        handle.log_info(format!("Installing rF2SharedMemoryMap plugin for {} to {}", game.name, dll_path.to_string_lossy()));
+        std::fs::copy(source, &dll_path).map_err(|e| format!("Failed to install {PLUGIN_DLL_NAME} to {}: {e}", dll_path.to_string_lossy()))?;
+    }
+
+    enable_plugin(handle, game_dir, config)
+}
+
+/// Checks the CustomPluginVariables.json has the plugin enabled
+pub(super) fn plugin_enabled(game_dir: &Path) -> bool {
+    std::fs::read_to_string(game_dir.join(PLUGIN_CONFIG_FILE)).ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|json| json.get(PLUGIN_DLL_NAME)?.get(" Enabled")?.as_i64())
+        == Some(1)
+}
+
+/// Enables the plugin and sets the buffer subscriptions, keeping all other entries as they are
+fn enable_plugin(handle: &PluginHandle, game_dir: &Path, config: &Config) -> Result<(), String> {
+    let path = game_dir.join(PLUGIN_CONFIG_FILE);
+
+    let original = if path.exists() {
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("Unable to read {}: {e}", path.to_string_lossy()))?;
+        serde_json::from_str::<Value>(&text).map_err(|e| format!("Unable to parse {}: {e}", path.to_string_lossy()))?
+    } else {
+        Value::Object(Map::new())
+    };
+
+    let mut updated = original.clone();
+    let root = updated.as_object_mut().ok_or(format!("{} does not contain a json object", path.to_string_lossy()))?;
+    let entry = root.entry(PLUGIN_DLL_NAME).or_insert_with(|| Value::Object(Map::new()));
+    let entry = entry.as_object_mut().ok_or(format!("{PLUGIN_DLL_NAME} entry in {} is not a json object", path.to_string_lossy()))?;
+
+    // The game writes the key with the leading space
+    entry.insert(" Enabled".to_string(), Value::from(1));
+    // Actions need it, but if the user turned it off we leave it off
+    entry.entry("EnableHWControlInput").or_insert_with(|| Value::from(1));
+    let mask = unsubscribed_buffers_mask(entry.get("UnsubscribedBuffersMask").and_then(Value::as_i64), config);
+    entry.insert("UnsubscribedBuffersMask".to_string(), Value::from(mask));
+
+    if updated == original {
+        handle.log_info("rF2SharedMemoryMap plugin already enabled");
+        return Ok(());
+    }
+
+    // Only the first backup is kept, so it stays the file as it was before we ever touched it
+    let backup = game_dir.join(PLUGIN_CONFIG_BACKUP_FILE);
+    if path.exists() && !backup.exists() {
+        std::fs::copy(&path, &backup).map_err(|e| format!("Unable to back up {} to {}: {e}", path.to_string_lossy(), backup.to_string_lossy()))?;
+    }
+
+    let text = serde_json::to_string_pretty(&updated).map_err(|e| format!("Unable to serialize {}: {e}", path.to_string_lossy()))?;
+    std::fs::write(&path, text).map_err(|e| format!("Unable to write {}: {e}", path.to_string_lossy()))?;
+
+    handle.log_info(format!("Enabled rF2SharedMemoryMap plugin in {}, restart the game if it is running", path.to_string_lossy()));
+    Ok(())
+}
+
+/// Everything we read has to be subscribed, so only those bits are cleared from the user's mask.
+/// Without a mask Graphics and Weather stay off if not used from the start,
+/// they are then requested through PluginControl once used (see control::BufferUses)
+fn unsubscribed_buffers_mask(current: Option<i64>, config: &Config) -> i64 {
+    let mut required = RF2SubscribedBuffer::Telemetry as i64
+        | RF2SubscribedBuffer::Scoring as i64
+        | RF2SubscribedBuffer::Rules as i64
+        | RF2SubscribedBuffer::MultiRules as i64
+        | RF2SubscribedBuffer::ForceFeedback as i64
+        | RF2SubscribedBuffer::PitInfo as i64;
+    if config.follow_camera {
+        required |= RF2SubscribedBuffer::Graphics as i64;
+    }
+    if config.weather {
+        required |= RF2SubscribedBuffer::Weather as i64;
+    }
+
+    let current = current.unwrap_or(RF2SubscribedBuffer::Graphics as i64 | RF2SubscribedBuffer::Weather as i64);
+    current & !required
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsubscribed_mask_keeps_user_bits() {
+        let config = Config::default();
+
+        // Defaults to leaving the unused pages off
+        assert_eq!(unsubscribed_buffers_mask(None, &config), 32 | 128);
+        // Pages we read are cleared, everything else the user set stays
+        assert_eq!(unsubscribed_buffers_mask(Some(0xff), &config), 32 | 128);
+        assert_eq!(unsubscribed_buffers_mask(Some(0), &config), 0);
+        assert_eq!(unsubscribed_buffers_mask(Some(0x100 | 128), &config), 0x100 | 128);
+
+        let config = Config { follow_camera: true, weather: true, ..Config::default() };
+        assert_eq!(unsubscribed_buffers_mask(None, &config), 0);
+        assert_eq!(unsubscribed_buffers_mask(Some(0x1ff), &config), 0x100);
+    }
+}
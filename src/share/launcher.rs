@@ -0,0 +1,178 @@
+use std::{ffi::OsStr, path::{Path, PathBuf}, process::{Command, Stdio}};
+
+use crate::config::LauncherConfig;
+
+/// Flatpak app id of protontricks
+const PROTONTRICKS_FLATPAK_ID: &'static str = "com.github.Matoking.protontricks";
+const PROTONTRICKS_LAUNCH: &'static str = "protontricks-launch";
+
+/// How we run the bridge inside the rF2 prefix
+#[derive(Debug, Clone)]
+pub(crate) enum Launcher {
+    /// protontricks-launch from the PATH, or ~/.local/bin for pipx
+    Native(PathBuf),
+    /// protontricks installed as a Flatpak
+    Flatpak,
+    /// User supplied command (with arguments) that behaves like protontricks-launch
+    Custom(Vec<String>),
+}
+
+impl Launcher {
+    /// Finds the launcher selected in the config, Auto tries native, then Flatpak
+    pub(crate) fn resolve(config: &LauncherConfig) -> Option<Launcher> {
+        let path = std::env::var_os("PATH");
+        let home = std::env::var_os("HOME");
+        Self::resolve_in(config, path.as_deref(), home.as_deref().map(Path::new))
+    }
+
+    /// resolve, but searching the given PATH and home
+    fn resolve_in(config: &LauncherConfig, path: Option<&OsStr>, home: Option<&Path>) -> Option<Launcher> {
+        match config {
+            LauncherConfig::Auto => find_native(path, home).or_else(|| find_flatpak(path)),
+            LauncherConfig::Native => find_native(path, home),
+            LauncherConfig::Flatpak => find_flatpak(path),
+            LauncherConfig::Custom(cmd) if !cmd.is_empty() => Some(Launcher::Custom(cmd.clone())),
+            LauncherConfig::Custom(_) => None,
+        }
+    }
+
+    /// Command to launch the exe, still requires the --appid, the exe and its arguments
+    pub(crate) fn command(&self, exe: &Path) -> Command {
+        match self {
+            Launcher::Native(path) => Command::new(path),
+            Launcher::Flatpak => {
+                let mut cmd = Command::new("flatpak");
+                cmd.arg("run");
+                // The sandbox can't see the exe otherwise (if the prefix is outside the granted folders)
+                if let Some(dir) = exe.parent() {
+                    cmd.arg(format!("--filesystem={}", dir.to_string_lossy()));
+                }
+                cmd.arg(format!("--command={PROTONTRICKS_LAUNCH}"))
+                    .arg(PROTONTRICKS_FLATPAK_ID);
+                cmd
+            },
+            Launcher::Custom(args) => {
+                let mut cmd = Command::new(&args[0]);
+                cmd.args(&args[1..]);
+                cmd
+            }
+        }
+    }
+}
+
+fn find_native(path: Option<&OsStr>, home: Option<&Path>) -> Option<Launcher> {
+    if let Some(path) = find_in_path(path, PROTONTRICKS_LAUNCH) {
+        return Some(Launcher::Native(path));
+    }
+
+    // pipx puts it into ~/.local/bin, which is not always in the PATH
+    let pipx = home?.join(".local/bin").join(PROTONTRICKS_LAUNCH);
+    if pipx.is_file() {
+        return Some(Launcher::Native(pipx));
+    }
+
+    None
+}
+
+fn find_flatpak(path: Option<&OsStr>) -> Option<Launcher> {
+    let flatpak = find_in_path(path, "flatpak")?;
+
+    let status = Command::new(flatpak)
+        .arg("info")
+        .arg(PROTONTRICKS_FLATPAK_ID)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Some(Launcher::Flatpak),
+        _ => None
+    }
+}
+
+fn find_in_path(path: Option<&OsStr>, name: &str) -> Option<PathBuf> {
+    std::env::split_paths(path?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsString, os::unix::fs::PermissionsExt};
+
+    use super::*;
+    use crate::share::TempDir;
+
+    /// Writes an executable shell script
+    fn fake_bin(dir: &Path, name: &str, script: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn path_of(dirs: &[&Path]) -> OsString {
+        std::env::join_paths(dirs).unwrap()
+    }
+
+    #[test]
+    fn native_from_path_or_pipx() {
+        let tmp = TempDir::new("launcher_native");
+        let bin = tmp.dir("bin");
+        let empty = tmp.dir("empty");
+        let home = tmp.dir("home");
+        let native = fake_bin(&bin, PROTONTRICKS_LAUNCH, "exit 0");
+
+        let path = path_of(&[&empty, &bin]);
+        for config in [LauncherConfig::Auto, LauncherConfig::Native] {
+            match Launcher::resolve_in(&config, Some(&path), Some(&home)) {
+                Some(Launcher::Native(found)) => assert_eq!(found, native),
+                other => panic!("{config:?} resolved to {other:?}"),
+            }
+        }
+
+        // Not in the PATH, but where pipx puts it
+        let pipx = fake_bin(&tmp.dir("home/.local/bin"), PROTONTRICKS_LAUNCH, "exit 0");
+        match Launcher::resolve_in(&LauncherConfig::Native, Some(&path_of(&[&empty])), Some(&home)) {
+            Some(Launcher::Native(found)) => assert_eq!(found, pipx),
+            other => panic!("pipx resolved to {other:?}"),
+        }
+
+        assert!(Launcher::resolve_in(&LauncherConfig::Native, Some(&path_of(&[&empty])), None).is_none());
+        assert!(Launcher::resolve_in(&LauncherConfig::Native, None, None).is_none());
+    }
+
+    #[test]
+    fn flatpak_only_if_protontricks_is_installed() {
+        let tmp = TempDir::new("launcher_flatpak");
+        let home = tmp.dir("home");
+        // Succeeds only for `flatpak info <protontricks id>`, like flatpak does with it installed
+        let installed = tmp.dir("installed");
+        fake_bin(&installed, "flatpak", &format!("[ \"$1\" = info ] && [ \"$2\" = {PROTONTRICKS_FLATPAK_ID} ]"));
+        let missing = tmp.dir("missing");
+        fake_bin(&missing, "flatpak", "exit 1");
+
+        let path = path_of(&[&installed]);
+        assert!(matches!(Launcher::resolve_in(&LauncherConfig::Auto, Some(&path), Some(&home)), Some(Launcher::Flatpak)));
+        assert!(matches!(Launcher::resolve_in(&LauncherConfig::Flatpak, Some(&path), Some(&home)), Some(Launcher::Flatpak)));
+        // Flatpak is not native
+        assert!(Launcher::resolve_in(&LauncherConfig::Native, Some(&path), Some(&home)).is_none());
+
+        let path = path_of(&[&missing]);
+        assert!(Launcher::resolve_in(&LauncherConfig::Auto, Some(&path), Some(&home)).is_none());
+        assert!(Launcher::resolve_in(&LauncherConfig::Flatpak, Some(&path), Some(&home)).is_none());
+
+        // Native wins over Flatpak in Auto
+        let native = fake_bin(&tmp.dir("native"), PROTONTRICKS_LAUNCH, "exit 0");
+        let path = path_of(&[&installed, native.parent().unwrap()]);
+        assert!(matches!(Launcher::resolve_in(&LauncherConfig::Auto, Some(&path), Some(&home)), Some(Launcher::Native(_))));
+    }
+
+    #[test]
+    fn custom_needs_a_command() {
+        assert!(Launcher::resolve_in(&LauncherConfig::Custom(Vec::new()), None, None).is_none());
+        let cmd = vec!["my-launch".to_string(), "--verbose".to_string()];
+        assert!(matches!(Launcher::resolve_in(&LauncherConfig::Custom(cmd), None, None), Some(Launcher::Custom(_))));
+    }
+}
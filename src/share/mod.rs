@@ -1,12 +1,23 @@
-use std::{ffi::{c_void, CString}, marker::PhantomData, mem::size_of, os::fd::{AsRawFd, FromRawFd, OwnedFd}, path::PathBuf, process::{Command, Stdio}};
+use std::{ffi::{c_void, CString}, marker::PhantomData, mem::size_of, os::fd::{AsRawFd, FromRawFd, OwnedFd}, path::{Path, PathBuf}, process::Stdio, sync::atomic::{fence, Ordering}};
 
 use datarace_plugin_api::wrappers::PluginHandle;
-use proton_finder::GameDrive;
 
-use crate::data::{PageScoring, PageTelemetry};
+/// Installing the bridge into the prefix
+mod install;
+/// Checks all dependencies are present
+mod preflight;
+/// Native/Flatpak/custom protontricks for launching the bridge
+mod launcher;
+/// Supported games
+mod game;
+
+pub(crate) use preflight::{GameChecks, Preflight};
+use launcher::Launcher;
+pub(crate) use game::{GameProfile, GAMES};
+
+use crate::{config::Config, data::{PageExtended, PageForceFeedback, PageGraphics, PageHWControl, PageHeader, PageInputHeader, PageMultiRules, PagePitInfo, PagePluginControl, PageRules, PageScoring, PageTelemetry, PageVehicleTelemetry, PageWeather, MAX_MAPPED_VEHICLES, PLUGIN_CONTROL_LAYOUT_VERSION}};
 
 const BRIDGE_EXE_NAME:&'static str = "shm-bridge-rf2.exe";
-const GAME_ID:u32 = 365960;
 
 // In case you are curious, YES, those $ marks are really in the memory map path
 // I could ask him why he did this, it causes hell when passed through cli,
@@ -31,43 +42,99 @@ const MM_WEATHER_FILE_NAME:&'static str = "$rFactor2SMMP_Weather$";
 /// 5 fps (plus on tracked callback from the game)
 const MM_EXTENDED_FILE_NAME:&'static str = "$rFactor2SMMP_Extended$";
 
+// Input buffers, these are read by the plugin, and only exist if enabled in the plugin config
+/// 5 fps
+const MM_HWCONTROL_FILE_NAME:&'static str = "$rFactor2SMMP_HWControl$";
+/// 1 fps
+const MM_PLUGIN_CONTROL_FILE_NAME:&'static str = "$rFactor2SMMP_PluginControl$";
+
+/// Oldest rF2SharedMemoryMap plugin version the page layouts in data.rs match
+const SUPPORTED_PLUGIN_VERSION: (u32, u32) = (3, 7);
+
+const PROTONTRICKS_HINT: &'static str = "Install protontricks (native, flatpak or pipx), or set DR_RF2_LAUNCHER";
+
 /// Checks if requirements are met
 /// If not install the software
-pub(crate) fn init_setup(handle: &PluginHandle) -> Result<(), String> {
-    let prefix = match proton_finder::get_game_drive(GAME_ID) {
-        Ok(res) => res,
-        Err(res) => {
-            handle.log_error("$STEAM_DIR was set, but no steam install found there!");
-            res
+/// Failures don't stop the plugin from loading, they are reported in the Preflight instead,
+/// and checked again with check_setup when a game is launched
+pub(crate) fn init_setup(handle: &PluginHandle, config: &Config) -> Preflight {
+    let launcher = Launcher::resolve(&config.launcher);
+    match &launcher {
+        Some(launcher) => handle.log_info(format!("Launching the bridge with {launcher:?}")),
+        None => handle.log_error(format!("No protontricks found for launcher {:?}", config.launcher))
+    }
+
+    let games = GAMES.each_ref().map(|game| setup_game(handle, config, game).0);
+    if !games.iter().any(|checks| checks.prefix.is_ok()) {
+        handle.log_error("No game prefix found! Make sure to install and launch the game at least once!");
+    }
+
+    let result = Preflight {
+        protontricks: preflight::check(launcher.is_some(), PROTONTRICKS_HINT),
+        steam: preflight::check(steamlocate::SteamDir::locate().is_some(), "Install Steam, or set $STEAM_DIR"),
+        games,
+    };
+
+    for (name, res) in result.checks() {
+        if let Err(hint) = res {
+            handle.log_error(format!("Setup check {name} failed: {hint}"));
         }
-    }.ok_or("rF2 Prefix not found! Make sure to install and launch the game at least once!".to_string())?;
+    }
 
-    let bridge_path = shm_bridge_path(&prefix).ok_or("Unable to find User/AppData/Local/DataRace within the rf2 prefix!".to_string())?;
+    result
+}
 
-    if !bridge_path.exists() {
-        // Installing bridge
-        
-        // TODO
-        return Err("bridge missing".to_string());
+/// Installs the bridge and plugin for the game, and checks the result.
+/// Returns the checks, and the path of the bridge if the prefix was found
+fn setup_game(handle: &PluginHandle, config: &Config, game: &GameProfile) -> (GameChecks, Option<PathBuf>) {
+    let flatpak_steam = flatpak_steam_root(std::env::var_os("HOME").as_deref().map(Path::new));
+    let prefix = find_prefix_local_dir(handle, game, flatpak_steam.as_deref());
+    if prefix.is_none() {
+        handle.log_info(format!("{} prefix not found", game.name));
     }
 
-    
-    // TODO check if bridge is already running
-    // Check if the game is running
-    
+    // Installing or updating the bridge
+    let bridge_path = prefix.clone().and_then(shm_bridge_path);
+    if let Some(bridge_path) = &bridge_path {
+        if let Err(e) = install::install_bridge(handle, config.bridge_source.as_deref(), bridge_path) {
+            handle.log_error(e);
+        }
+    } else if prefix.is_some() {
+        handle.log_error(format!("Unable to find User/AppData/Local/DataRace within the {} prefix!", game.name));
+    }
+
+    // Installing rF2SharedMemoryMap plugin and enabling it
+    let game_dir = install::game_dir(game);
+    if let Some(game_dir) = &game_dir {
+        if let Err(e) = install::install_plugin(handle, game_dir, game, config) {
+            handle.log_error(e);
+        }
+    } else {
+        handle.log_info(format!("Unable to locate the {} install through steam", game.name));
+    }
 
-    // TODO install rF2SharedMemoryMap Plugin if not present
-    // And make sure it is enabled, for which we need to parse
-    // UserData/player/CustomPluginVariables.json
+    let bridge_path = bridge_path.filter(|path| path.exists());
+    let checks = GameChecks {
+        prefix: preflight::check(prefix.is_some(), "Install the game and launch it once through Steam"),
+        bridge: preflight::check(bridge_path.is_some(), "Set DR_RF2_BRIDGE_SOURCE to shm-bridge-rf2.exe"),
+        plugin_dll: preflight::check(game_dir.as_deref().is_some_and(|dir| install::plugin_dll_path(dir, game).exists()), "Set DR_RF2_PLUGIN_SOURCE to rFactor2SharedMemoryMapPlugin64.dll"),
+        plugin_enabled: preflight::check(game_dir.as_deref().is_some_and(install::plugin_enabled), "Enable rFactor2SharedMemoryMapPlugin64.dll in CustomPluginVariables.json"),
+    };
 
-    // TODO check protontricks exists
+    // Games that are not installed are expected, so we only report games we found
+    if checks.prefix.is_ok() {
+        for (name, res) in checks.checks() {
+            if let Err(hint) = res {
+                handle.log_error(format!("Setup check {}.{name} failed: {hint}", game.key));
+            }
+        }
+    }
 
-    Ok(())
+    (checks, bridge_path)
 }
 
-fn shm_bridge_path(prefix: &GameDrive) -> Option<PathBuf> {
-    let mut path = prefix.config_local_dir()?;
-    
+/// Bridge within AppData/Local of the prefix, creating the DataRace folder if needed
+fn shm_bridge_path(mut path: PathBuf) -> Option<PathBuf> {
     path.push("DataRace");
     if !path.exists() {
         std::fs::create_dir(path.as_path()).ok()?;
@@ -80,34 +147,86 @@ fn shm_bridge_path(prefix: &GameDrive) -> Option<PathBuf> {
 pub(crate) struct GameRunningHelperState {
     running: Option<sysinfo::Pid>,
     bridge: Option<sysinfo::Pid>,
-    bridge_path: PathBuf
+    /// Index into GAMES of the one running
+    game: Option<usize>,
+    /// Bridge within the prefix of the running game, set by check_setup
+    bridge_path: Option<PathBuf>,
+    /// Resolved again by check_setup, in case protontricks got installed
+    launcher: Option<Launcher>,
 }
 
 impl GameRunningHelperState {
-    pub(crate) fn new(handle: &PluginHandle) -> Option<Self> {
+    pub(crate) fn new() -> Self {
         // Sysinfo keeps the files open, and we kind of don't want that
         sysinfo::set_open_files_limit(0);
 
-        let prefix = match proton_finder::get_game_drive(GAME_ID) {
-            Ok(res) => res,
-            Err(res) => {
-                handle.log_error("$STEAM_DIR was set, but no steam install found there!");
-                res
-            }
-        }?; // Yes, this doesn't log proper errors, but if this fails the plugin would have failed
-        // init with error messages
-        let path = shm_bridge_path(&prefix)?;
+        GameRunningHelperState {
+            running: None,
+            bridge: None,
+            game: None,
+            bridge_path: None,
+            launcher: None,
+        }
+    }
+
+    /// The game detected running (or last running)
+    pub(crate) fn game(&self) -> Option<&'static GameProfile> {
+        self.game.map(|index| &GAMES[index])
+    }
+}
 
-        if !path.exists() {
-            return None;
+/// Flatpak Steam keeps its library within the sandbox data folder
+const FLATPAK_STEAM_DIR: &'static str = ".var/app/com.valvesoftware.Steam/.local/share/Steam";
+/// Where proton puts AppData/Local within the compatdata of a game
+const PREFIX_LOCAL_DIR: &'static str = "pfx/drive_c/users/steamuser/AppData/Local";
+
+/// The Flatpak Steam root within home, if it is installed
+fn flatpak_steam_root(home: Option<&Path>) -> Option<PathBuf> {
+    let root = home?.join(FLATPAK_STEAM_DIR);
+    root.is_dir().then_some(root)
+}
+
+/// Finds AppData/Local within the game prefix, falling back to the Flatpak Steam root if the native install doesn't have it
+fn find_prefix_local_dir(handle: &PluginHandle, game: &GameProfile, flatpak_steam: Option<&Path>) -> Option<PathBuf> {
+    match proton_finder::get_game_drive(game.app_id) {
+        Ok(Some(res)) => return res.config_local_dir(),
+        Ok(None) => (),
+        Err(res) => {
+            handle.log_error("$STEAM_DIR was set, but no steam install found there!");
+            return res.and_then(|drive| drive.config_local_dir());
         }
+    }
 
-        Some(GameRunningHelperState {
-            running: None,
-            bridge: None,
-            bridge_path: path
-        })
+    // proton_finder only takes the steam root from $STEAM_DIR, so we look through the Flatpak root ourselves
+    let dir = prefix_local_dir_in(flatpak_steam?, game.app_id)?;
+    handle.log_info(format!("Found the {} prefix in Flatpak Steam at {}", game.name, dir.to_string_lossy()));
+    Some(dir)
+}
+
+/// Looks for the prefix of the app in every library of the steam root
+fn prefix_local_dir_in(steam_root: &Path, app_id: u32) -> Option<PathBuf> {
+    steam_libraries(steam_root).into_iter()
+        .map(|library| library.join("steamapps/compatdata").join(app_id.to_string()).join(PREFIX_LOCAL_DIR))
+        .find(|dir| dir.is_dir())
+}
+
+/// The steam root, and the libraries listed in its libraryfolders.vdf
+fn steam_libraries(steam_root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam_root.to_path_buf()];
+
+    // Lines look like: "path"		"/home/user/Games/SteamLibrary"
+    let text = std::fs::read_to_string(steam_root.join("steamapps/libraryfolders.vdf")).unwrap_or_default();
+    for line in text.lines() {
+        let mut parts = line.split('"').filter(|part| !part.trim().is_empty());
+        if let (Some("path"), Some(path)) = (parts.next(), parts.next()) {
+            let path = PathBuf::from(path);
+            if !libraries.contains(&path) {
+                libraries.push(path);
+            }
+        }
     }
+
+    libraries
 }
 
 /// This checks if a certain process (with a certain cmdline) is running, and retrieves the pid.
@@ -167,16 +286,43 @@ fn cmd_combiner(cmd: &[String]) -> String {
     output
 }
 
-/// Checks if the game is running
+/// Checks if any of the supported games is running
 pub(crate) fn check_if_game_running(helper_state: &mut GameRunningHelperState) -> bool {
-    // We find the stable entry process which cmdline looks something like this:
-    // Z:\home\Lukas\.local\share\Steam\steamapps\common\Assetto Corsa Competizione\acc.exe
-    // let rf2_bin_name = "rFactor 2/Launcher/Launch rFactor.exe".to_string();
-    let rf2_bin_name = "rFactor 2\\Bin64\\rFactor2.exe".to_string();
-     
+    if let Some(index) = helper_state.game {
+        let fragment = GAMES[index].exe_fragment.to_string();
+        helper_state.running = check_for_program_running(helper_state.running, fragment);
+        if helper_state.running.is_some() {
+            return true;
+        }
+    }
+
+    for (index, game) in GAMES.iter().enumerate() {
+        let running = check_for_program_running(None, game.exe_fragment.to_string());
+        if running.is_some() {
+            helper_state.running = running;
+            helper_state.game = Some(index);
+            return true;
+        }
+    }
+
+    helper_state.running = None;
+    false
+}
+
+/// Runs the setup of the running game again, and updates its checks in the preflight.
+/// Returns if the essentials to connect (protontricks, prefix and bridge) are there
+pub(crate) fn check_setup(handle: &PluginHandle, config: &Config, helper_state: &mut GameRunningHelperState, preflight: &mut Preflight) -> bool {
+    helper_state.launcher = Launcher::resolve(&config.launcher);
+    preflight.protontricks = preflight::check(helper_state.launcher.is_some(), PROTONTRICKS_HINT);
 
-    helper_state.running = check_for_program_running(helper_state.running, rf2_bin_name);
-    helper_state.running.is_some()
+    let Some(index) = helper_state.game else {
+        return false;
+    };
+    let (checks, bridge_path) = setup_game(handle, config, &GAMES[index]);
+    preflight.games[index] = checks;
+    helper_state.bridge_path = bridge_path;
+
+    helper_state.launcher.is_some() && checks.ready()
 }
 
 fn check_for_bridge(helper_state: &mut GameRunningHelperState) -> bool {
@@ -191,19 +337,41 @@ pub(crate) fn connect(handle: &PluginHandle, helper_state: &mut GameRunningHelpe
         handle.log_info("bridge was not running, launching bridge");
 
         // Spawning a new bridge process
-        let res = Command::new("protontricks-launch")
+        let game = helper_state.game().ok_or("No game running to launch the bridge for".to_string())?;
+        let bridge_path = helper_state.bridge_path.as_ref().ok_or(format!("No bridge installed for {}", game.name))?;
+        let launcher = helper_state.launcher.as_ref().ok_or("No protontricks found to launch the bridge with".to_string())?;
+
+        let res = launcher.command(bridge_path)
             .arg("--appid")
-            .arg(GAME_ID.to_string())
-            .arg(helper_state.bridge_path.as_os_str())
+            .arg(game.app_id.to_string())
+            .arg(bridge_path.as_os_str())
             
             .arg("--map")
             .arg(MM_TELEMETRY_FILE_NAME)
             .arg(MM_SCORING_FILE_NAME)
+            .arg(MM_RULES_FILE_NAME)
+            .arg(MM_MULTI_RULES_FILE_NAME)
+            .arg(MM_EXTENDED_FILE_NAME)
+            .arg(MM_FORCE_FEEDBACK_FILE_NAME)
+            .arg(MM_GRAPHICS_FILE_NAME)
+            .arg(MM_PITINFO_FILE_NAME)
+            .arg(MM_WEATHER_FILE_NAME)
+            .arg(MM_HWCONTROL_FILE_NAME)
+            .arg(MM_PLUGIN_CONTROL_FILE_NAME)
 
 
             .arg("--size")
             .arg(size_of::<PageTelemetry>().to_string())
             .arg(size_of::<PageScoring>().to_string())
+            .arg(size_of::<PageRules>().to_string())
+            .arg(size_of::<PageMultiRules>().to_string())
+            .arg(size_of::<PageExtended>().to_string())
+            .arg(size_of::<PageForceFeedback>().to_string())
+            .arg(size_of::<PageGraphics>().to_string())
+            .arg(size_of::<PagePitInfo>().to_string())
+            .arg(size_of::<PageWeather>().to_string())
+            .arg(size_of::<PageHWControl>().to_string())
+            .arg(size_of::<PagePluginControl>().to_string())
 
             .stdin(Stdio::null())
             .stdout(Stdio::null())
@@ -229,10 +397,101 @@ pub(crate) fn connect(handle: &PluginHandle, helper_state: &mut GameRunningHelpe
     }
 
     // Mounting the memory maps
-    Ok(MapHolder {
+    let holder = MapHolder {
         telemetry: SharedMemory::<PageTelemetry>::connect(MM_TELEMETRY_FILE_NAME)?,
-        scoring: SharedMemory::<PageScoring>::connect(MM_SCORING_FILE_NAME)?
-    })
+        scoring: SharedMemory::<PageScoring>::connect(MM_SCORING_FILE_NAME)?,
+        // Extended stays required, as we verify the plugin version with it
+        extended: SharedMemory::<PageExtended>::connect(MM_EXTENDED_FILE_NAME)?,
+        // Without these we still read telemetry and scoring, and skip their properties
+        rules: mount_optional(handle, MM_RULES_FILE_NAME),
+        multi_rules: mount_optional(handle, MM_MULTI_RULES_FILE_NAME),
+        force_feedback: mount_optional(handle, MM_FORCE_FEEDBACK_FILE_NAME),
+        pit_info: mount_optional(handle, MM_PITINFO_FILE_NAME),
+        // Graphics and Weather are not subscribed by default, so we don't fail if they are not there
+        graphics: mount_optional(handle, MM_GRAPHICS_FILE_NAME),
+        weather: mount_optional(handle, MM_WEATHER_FILE_NAME),
+        // Input buffers are disabled in the plugin config by default
+        hw_control: mount_optional_input(handle, MM_HWCONTROL_FILE_NAME),
+        plugin_control: mount_optional_input(handle, MM_PLUGIN_CONTROL_FILE_NAME),
+    };
+
+    let (extended, _) = holder.extended.snapshot().map_err(|attempts| format!("Extended page was torn on all {attempts} reads"))?;
+    check_plugin_version(handle, &extended)?;
+
+    Ok(holder)
+}
+
+fn mount_optional<T>(handle: &PluginHandle, name: &str) -> Option<SharedMemory<T>> {
+    match SharedMemory::<T>::connect(name) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            handle.log_info(format!("Optional page not available: {e}"));
+            None
+        }
+    }
+}
+
+fn mount_optional_input<T>(handle: &PluginHandle, name: &str) -> Option<InputBuffer<T>> {
+    match InputBuffer::<T>::connect(name) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            handle.log_info(format!("Input buffer not available: {e}"));
+            None
+        }
+    }
+}
+
+/// Asks the rF2SharedMemoryMap plugin to enable the pages in the mask (see control::BufferUses)
+/// Returns if a request was sent, the requested pages then have to be mounted with MapHolder::mount_missing
+pub(crate) fn request_buffers(handle: &PluginHandle, holder: &MapHolder, mask: i32) -> bool {
+    let plugin_control = match &holder.plugin_control {
+        Some(plugin_control) => plugin_control,
+        None => {
+            if mask != 0 {
+                handle.log_error("PluginControl input buffer not available, unable to request Graphics/Weather, enable them in CustomPluginVariables.json instead");
+            }
+            return false;
+        }
+    };
+
+    plugin_control.write(PLUGIN_CONTROL_LAYOUT_VERSION, |page| {
+        page.request_enable_buffers_mask = mask;
+        // Actions are always available, so we always want HWControl
+        page.request_hw_control_input = 1;
+        page.request_weather_control_input = 0;
+        page.request_rules_control_input = 0;
+    });
+    handle.log_info(format!("Requested buffers {mask} and HWControl input from the rF2SharedMemoryMap plugin"));
+
+    true
+}
+
+/// Uses the Extended page to verify the rF2SharedMemoryMap plugin matches our structs
+fn check_plugin_version(handle: &PluginHandle, extended: &PageExtended) -> Result<(), String> {
+    let raw = extended.version;
+    let end = raw.iter().position(|c| *c == 0).unwrap_or(raw.len());
+    let version = String::from_utf8_lossy(&raw[..end]).to_string();
+
+    if version.is_empty() {
+        // The plugin writes this once loaded, which can be after we connected
+        handle.log_info("Extended page not yet written, unable to verify rF2SharedMemoryMap plugin version");
+        return Ok(());
+    }
+
+    if extended.is64bit == 0 {
+        return Err(format!("rF2SharedMemoryMap plugin {version} is not the 64bit version, layout is incompatible"));
+    }
+
+    let mut split = version.split('.').map(|part| part.trim().parse::<u32>().unwrap_or(0));
+    let major = split.next().unwrap_or(0);
+    let minor = split.next().unwrap_or(0);
+
+    if (major, minor) < SUPPORTED_PLUGIN_VERSION {
+        return Err(format!("rF2SharedMemoryMap plugin {version} is too old, {}.{} or newer is required", SUPPORTED_PLUGIN_VERSION.0, SUPPORTED_PLUGIN_VERSION.1));
+    }
+
+    handle.log_info(format!("rF2SharedMemoryMap plugin version {version}"));
+    Ok(())
 }
 
 pub(crate) fn disconnect(handle: &PluginHandle, helper_state: &mut GameRunningHelperState, holder: Option<MapHolder>) {
@@ -270,7 +529,56 @@ pub(crate) fn disconnect(handle: &PluginHandle, helper_state: &mut GameRunningHe
 /// Holds all the memory maps
 pub struct MapHolder {
     pub telemetry: SharedMemory<PageTelemetry>,
-    pub scoring: SharedMemory<PageScoring>
+    pub scoring: SharedMemory<PageScoring>,
+    pub rules: Option<SharedMemory<PageRules>>,
+    /// Only written on session transitions
+    pub multi_rules: Option<SharedMemory<PageMultiRules>>,
+    pub extended: SharedMemory<PageExtended>,
+    pub force_feedback: Option<SharedMemory<PageForceFeedback>>,
+    pub pit_info: Option<SharedMemory<PagePitInfo>>,
+    /// Contains the car the camera is focused on, but is unsubscribed by default
+    pub graphics: Option<SharedMemory<PageGraphics>>,
+    /// Weather control target, also unsubscribed by default
+    pub weather: Option<SharedMemory<PageWeather>>,
+    /// Sends control inputs (pit menu etc.) to the game
+    pub hw_control: Option<InputBuffer<PageHWControl>>,
+    /// Requests additional pages from the plugin at runtime
+    pub plugin_control: Option<InputBuffer<PagePluginControl>>,
+}
+
+impl MapHolder {
+    /// Mounts the optional pages and input buffers that were not available yet,
+    /// e.g. after they were requested through request_buffers, or the bridge was still creating them.
+    /// Returns true if all are mounted
+    pub(crate) fn mount_missing(&mut self, handle: &PluginHandle) -> bool {
+        if self.rules.is_none() {
+            self.rules = mount_optional(handle, MM_RULES_FILE_NAME);
+        }
+        if self.multi_rules.is_none() {
+            self.multi_rules = mount_optional(handle, MM_MULTI_RULES_FILE_NAME);
+        }
+        if self.force_feedback.is_none() {
+            self.force_feedback = mount_optional(handle, MM_FORCE_FEEDBACK_FILE_NAME);
+        }
+        if self.pit_info.is_none() {
+            self.pit_info = mount_optional(handle, MM_PITINFO_FILE_NAME);
+        }
+        if self.graphics.is_none() {
+            self.graphics = mount_optional(handle, MM_GRAPHICS_FILE_NAME);
+        }
+        if self.weather.is_none() {
+            self.weather = mount_optional(handle, MM_WEATHER_FILE_NAME);
+        }
+        if self.hw_control.is_none() {
+            self.hw_control = mount_optional_input(handle, MM_HWCONTROL_FILE_NAME);
+        }
+        if self.plugin_control.is_none() {
+            self.plugin_control = mount_optional_input(handle, MM_PLUGIN_CONTROL_FILE_NAME);
+        }
+
+        self.rules.is_some() && self.multi_rules.is_some() && self.force_feedback.is_some() && self.pit_info.is_some()
+            && self.graphics.is_some() && self.weather.is_some() && self.hw_control.is_some() && self.plugin_control.is_some()
+    }
 }
 
 // Simetry
@@ -309,43 +617,455 @@ unsafe impl<T: Sync + std::fmt::Debug> Sync for SharedMemory<T> {}
 
 impl<T> SharedMemory<T> {
     fn connect(foo: &str) -> Result<Self, String> {
-        let path = CString::new(format!("/{foo}")).expect("We should be able to build this static C string");
+        let (fd, memory) = open_map(foo, std::mem::size_of::<T>(), false)?;
+
+        Ok(Self {
+            _fd: fd,
+            memory,
+            phantom_data: Default::default(),
+        })
+    }
+
+    /// Version the game started writing last, without copying anything
+    /// T has to start with a PageHeader
+    pub fn version(&self) -> u32 {
+        let header = self.memory as *const PageHeader;
+        unsafe { std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_begin)) }
+    }
+
+    /// Copies the whole page out of the memory map, without a torn frame.
+    /// T has to start with a PageHeader.
+    ///
+    /// Returns the copy with how many retries it took, or Err with the number of attempts if every one was torn
+    pub fn snapshot(&self) -> Result<(T, u32), u32>
+    where T: Copy {
+        self.read_with(|page| unsafe { std::ptr::read_volatile(page) })
+    }
+
+    /// Reads from the page without a torn frame, T has to start with a PageHeader.
+    /// The game increments begin before writing and sets end to it after, so a copy made while
+    /// begin == end, and begin unchanged afterwards, was not written to in the meantime.
+    /// Torn copies are retried up to READ_RETRIES times.
+    ///
+    /// extract gets the pointer into the map, and has to copy out with volatile reads.
+    /// We never hand out references, as the game writes into the map while we hold them
+    fn read_with<R, F: Fn(*const T) -> R>(&self, extract: F) -> Result<(R, u32), u32> {
+        let header = self.memory as *const PageHeader;
+
+        for attempt in 0..=READ_RETRIES {
+            if attempt > 0 {
+                backoff(attempt);
+            }
+
+            unsafe {
+                let begin = std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_begin));
+                fence(Ordering::Acquire);
+                let end = std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_end));
+                if begin != end {
+                    // Write in progress
+                    continue;
+                }
+                fence(Ordering::Acquire);
 
-        let fd = unsafe { libc::shm_open(path.as_ptr(), libc::SHM_RDONLY, 0) };
+                let res = extract(self.memory as *const T);
 
-        if fd == -1 {
-            Err(format!("Opening the {} file failed: {}", path.to_string_lossy(), std::io::Error::last_os_error().to_string()))
+                fence(Ordering::Acquire);
+                if std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_begin)) == begin {
+                    return Ok((res, attempt));
+                }
+            }
+        }
+
+        Err(READ_RETRIES + 1)
+    }
+}
+
+/// A single vehicle copied out of the telemetry page
+#[derive(Debug, Clone, Copy)]
+pub struct VehicleRead {
+    /// Version of the page it was copied from
+    pub version: u32,
+    pub num_vehicles: i32,
+    pub vehicle: PageVehicleTelemetry,
+}
+
+impl SharedMemory<PageTelemetry> {
+    /// Copies only the vehicle at index, as the whole page is big (same torn frame checks as snapshot)
+    /// Panics if index is not below MAX_MAPPED_VEHICLES
+    pub fn read_vehicle(&self, index: usize) -> Result<(VehicleRead, u32), u32> {
+        assert!(index < MAX_MAPPED_VEHICLES, "Vehicle index {index} out of range");
+
+        self.read_with(|page| unsafe {
+            VehicleRead {
+                version: std::ptr::read_volatile(std::ptr::addr_of!((*page).header.version_update_begin)),
+                num_vehicles: std::ptr::read_volatile(std::ptr::addr_of!((*page).num_vehicles)),
+                vehicle: std::ptr::read_volatile(std::ptr::addr_of!((*page).vehicles[index])),
+            }
+        })
+    }
+}
+
+impl SharedMemory<PageForceFeedback> {
+    /// The page has no versions, the game writes the single f64 atomically
+    pub fn force(&self) -> f64 {
+        let page = self.memory as *const PageForceFeedback;
+        unsafe { std::ptr::read_volatile(std::ptr::addr_of!((*page).force_value)) }
+    }
+}
+
+/// How often a torn read is retried before giving up on the frame
+pub const READ_RETRIES: u32 = 4;
+
+/// Waits before the retry of a torn read. The game writes a page within microseconds,
+/// so we spin a little first, and later yield so the writer can finish if it shares our core
+fn backoff(attempt: u32) {
+    if attempt <= READ_RETRIES / 2 {
+        for _ in 0..(32 << attempt) {
+            std::hint::spin_loop();
+        }
+    } else {
+        std::thread::yield_now();
+    }
+}
+
+/// Opens and mmaps the shm file, read only unless writable is set
+fn open_map(name: &str, len: usize, writable: bool) -> Result<(OwnedFd, *mut c_void), String> {
+    let path = CString::new(format!("/{name}")).expect("We should be able to build this static C string");
+    let (oflag, prot) = if writable {
+        (libc::O_RDWR, libc::PROT_READ | libc::PROT_WRITE)
+    } else {
+        (libc::SHM_RDONLY, libc::PROT_READ)
+    };
+
+    let fd = unsafe { libc::shm_open(path.as_ptr(), oflag, 0) };
+
+    if fd == -1 {
+        Err(format!("Opening the {} file failed: {}", path.to_string_lossy(), std::io::Error::last_os_error().to_string()))
+    } else {
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let memory = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                prot,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+
+        if memory == libc::MAP_FAILED {
+            Err(format!("Unable to mmap the opened SHM file {}: {}", path.to_string_lossy(), std::io::Error::last_os_error().to_string()))
         } else {
-            let len = std::mem::size_of::<T>();
-            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
-
-            let memory = unsafe {
-                libc::mmap(
-                    std::ptr::null_mut(),
-                    len,
-                    libc::PROT_READ,
-                    libc::MAP_SHARED,
-                    fd.as_raw_fd(),
-                    0,
-                )
-            };
-
-            if memory == libc::MAP_FAILED {
-                Err(format!("Unable to mmap the opened SHM file {}: {}", path.to_string_lossy(), std::io::Error::last_os_error().to_string()))
-            } else {
-                Ok(Self {
-                    _fd: fd,
-                    memory,
-                    phantom_data: Default::default(),
-                })
+            Ok((fd, memory))
+        }
+    }
+}
+
+/// Writable counterpart to SharedMemory, for the input buffers of the rF2SharedMemoryMap plugin
+/// T has to start with a PageInputHeader
+///
+/// Writes only reach the game if shm-bridge copies the mapping from Linux back into the Windows one.
+/// If it only mirrors Windows to Linux, our write is replaced with the game's unchanged buffer, holds tells
+pub struct InputBuffer<T> {
+    _fd: OwnedFd,
+    memory: *mut c_void,
+    phantom_data: PhantomData<T>,
+}
+
+// Same as SharedMemory, writes only happen from the updater thread that holds the MapHolder
+unsafe impl<T: Send + std::fmt::Debug> Send for InputBuffer<T> {}
+unsafe impl<T: Sync + std::fmt::Debug> Sync for InputBuffer<T> {}
+
+impl<T> InputBuffer<T> {
+    /// Mounts the input buffer with the name (without the leading /)
+    /// Can also be used on a local shm file that stands in for the game
+    pub fn connect(name: &str) -> Result<Self, String> {
+        let (fd, memory) = open_map(name, std::mem::size_of::<T>(), true)?;
+
+        Ok(Self {
+            _fd: fd,
+            memory,
+            phantom_data: Default::default(),
+        })
+    }
+
+    /// Writes into the buffer following the version convention of the plugin:
+    /// begin is incremented, the data written, and then end is set to match begin.
+    /// The plugin only picks up the buffer when both versions match and changed since its last read
+    ///
+    /// fill gets a copy of the current content, as we never hand out references into the map.
+    /// Returns the version written
+    pub fn write<F: FnOnce(&mut T)>(&self, layout_version: i32, fill: F) -> u32
+    where T: Copy {
+        let page = self.memory as *mut T;
+        let header = self.memory as *mut PageInputHeader;
+
+        unsafe {
+            let mut local = std::ptr::read_volatile(page);
+            fill(&mut local);
+
+            let begin = std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_begin));
+            let end = std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_end));
+            let version = begin.wrapping_add(1);
+
+            // The copy goes out with the new begin, but still the old end, so it reads as in progress until we are done
+            let local_header = std::ptr::addr_of_mut!(local) as *mut PageInputHeader;
+            std::ptr::addr_of_mut!((*local_header).version_update_begin).write_unaligned(version);
+            std::ptr::addr_of_mut!((*local_header).version_update_end).write_unaligned(end);
+            std::ptr::addr_of_mut!((*local_header).layout_version).write_unaligned(layout_version);
+
+            std::ptr::write_volatile(std::ptr::addr_of_mut!((*header).version_update_begin), version);
+            fence(Ordering::Release);
+
+            std::ptr::write_volatile(page, local);
+
+            fence(Ordering::Release);
+            std::ptr::write_volatile(std::ptr::addr_of_mut!((*header).version_update_end), version);
+
+            version
+        }
+    }
+
+    /// If the buffer still holds the version we wrote.
+    /// Only the bridge writes into the mapping besides us, so once it had time to sync (some 100ms),
+    /// a different version means it mirrored the game's buffer over ours, and the write never reached the game
+    pub fn holds(&self, version: u32) -> bool {
+        let header = self.memory as *const PageInputHeader;
+        unsafe {
+            std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_begin)) == version
+                && std::ptr::read_volatile(std::ptr::addr_of!((*header).version_update_end)) == version
+        }
+    }
+}
+
+/// Creates a shm file to stand in for the game's mapping, removed on drop
+#[cfg(test)]
+pub(crate) struct LocalShm {
+    pub name: String,
+}
+
+#[cfg(test)]
+impl LocalShm {
+    pub(crate) fn new(name: &str, len: usize) -> Self {
+        let name = format!("dr_rf2_test_{name}_{}", std::process::id());
+        let path = CString::new(format!("/{name}")).unwrap();
+
+        unsafe {
+            let fd = libc::shm_open(path.as_ptr(), libc::O_CREAT | libc::O_RDWR | libc::O_TRUNC, 0o600);
+            assert!(fd != -1, "shm_open failed: {}", std::io::Error::last_os_error());
+            assert!(libc::ftruncate(fd, len as libc::off_t) == 0, "ftruncate failed: {}", std::io::Error::last_os_error());
+            libc::close(fd);
+        }
+
+        LocalShm { name }
+    }
+
+    /// Current content of the file
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        std::fs::read(format!("/dev/shm/{}", self.name)).unwrap()
+    }
+}
+
+#[cfg(test)]
+impl Drop for LocalShm {
+    fn drop(&mut self) {
+        let path = CString::new(format!("/{}", self.name)).unwrap();
+        unsafe { libc::shm_unlink(path.as_ptr()); }
+    }
+}
+
+/// Folder in the temp dir, removed again on drop
+#[cfg(test)]
+pub(crate) struct TempDir {
+    pub path: PathBuf,
+}
+
+#[cfg(test)]
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("dr_rf2_test_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        TempDir { path }
+    }
+
+    /// Creates the folder (and its parents) within
+    pub(crate) fn dir(&self, relative: &str) -> PathBuf {
+        let path = self.path.join(relative);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::HW_CONTROL_LAYOUT_VERSION;
+
+    #[test]
+    fn flatpak_prefix_in_any_library() {
+        let tmp = TempDir::new("flatpak_prefix");
+        let home = tmp.dir("home");
+        assert_eq!(flatpak_steam_root(Some(&home)), None);
+
+        let root = tmp.dir(&format!("home/{FLATPAK_STEAM_DIR}"));
+        assert_eq!(flatpak_steam_root(Some(&home)), Some(root.clone()));
+        assert_eq!(prefix_local_dir_in(&root, 365960), None);
+
+        // Prefixes live in the library the game is installed to
+        let library = tmp.dir("library");
+        std::fs::create_dir_all(root.join("steamapps")).unwrap();
+        std::fs::write(root.join("steamapps/libraryfolders.vdf"), format!(
+            "\"libraryfolders\"\n{{\n\t\"0\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n\t\"1\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t\t\"apps\"\n\t\t{{\n\t\t\t\"365960\"\t\t\"1234\"\n\t\t}}\n\t}}\n}}\n",
+            root.to_string_lossy(), library.to_string_lossy(),
+        )).unwrap();
+        assert_eq!(steam_libraries(&root), vec![root.clone(), library.clone()]);
+
+        let rf2 = tmp.dir(&format!("library/steamapps/compatdata/365960/{PREFIX_LOCAL_DIR}"));
+        let lmu = tmp.dir(&format!("home/{FLATPAK_STEAM_DIR}/steamapps/compatdata/2399420/{PREFIX_LOCAL_DIR}"));
+        assert_eq!(prefix_local_dir_in(&root, 365960), Some(rf2));
+        assert_eq!(prefix_local_dir_in(&root, 2399420), Some(lmu));
+        assert_eq!(prefix_local_dir_in(&root, 1), None);
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn input_buffer_write_sets_versions_and_payload() {
+        let shm = LocalShm::new("input_buffer", size_of::<PageHWControl>());
+        let buffer = InputBuffer::<PageHWControl>::connect(&shm.name).unwrap();
+
+        for (version, control) in [(1, "PitRequest"), (2, "Headlights")] {
+            buffer.write(HW_CONTROL_LAYOUT_VERSION, |page| {
+                let mut name = [0; 96];
+                name[..control.len()].copy_from_slice(control.as_bytes());
+                page.control_name = name;
+                page.ret_val = 1.0;
+            });
+
+            let bytes = shm.bytes();
+            assert_eq!(u32_at(&bytes, 0), version, "version_update_begin");
+            assert_eq!(u32_at(&bytes, 4), version, "version_update_end");
+            assert_eq!(u32_at(&bytes, 8), HW_CONTROL_LAYOUT_VERSION as u32, "layout_version");
+
+            let name = &bytes[12..12 + 96];
+            assert_eq!(&name[..control.len()], control.as_bytes());
+            assert!(name[control.len()..].iter().all(|c| *c == 0));
+            assert_eq!(f64::from_le_bytes(bytes[108..116].try_into().unwrap()), 1.0);
+        }
+    }
+
+    #[test]
+    fn input_buffer_notices_overwritten_writes() {
+        let shm = LocalShm::new("input_buffer_overwritten", size_of::<PageHWControl>());
+        let buffer = InputBuffer::<PageHWControl>::connect(&shm.name).unwrap();
+
+        let version = buffer.write(HW_CONTROL_LAYOUT_VERSION, |page| page.ret_val = 1.0);
+        assert_eq!(version, 1);
+        assert!(buffer.holds(version));
+
+        // A bridge mirroring only from the game copies the game's untouched buffer over ours
+        std::fs::write(format!("/dev/shm/{}", shm.name), vec![0u8; size_of::<PageHWControl>()]).unwrap();
+        assert!(!buffer.holds(version));
+    }
+
+    /// Page with a payload large enough that a copy can be torn
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug)]
+    struct StressPage {
+        header: PageHeader,
+        values: [u64; 512],
+    }
+
+    #[test]
+    fn snapshot_is_never_torn_under_a_concurrent_writer() {
+        use std::sync::{atomic::AtomicBool, Arc};
+
+        let shm = LocalShm::new("stress", size_of::<StressPage>());
+        let reader = SharedMemory::<StressPage>::connect(&shm.name).unwrap();
+
+        // Writes like the plugin: begin, then every value set to the new version, then end
+        let (_fd, memory) = open_map(&shm.name, size_of::<StressPage>(), true).unwrap();
+        let memory = memory as usize;
+        let stop = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let page = memory as *mut StressPage;
+                let mut version = 0u32;
+                while !stop.load(Ordering::Relaxed) {
+                    version = version.wrapping_add(1);
+                    unsafe {
+                        std::ptr::write_volatile(std::ptr::addr_of_mut!((*page).header.version_update_begin), version);
+                        fence(Ordering::Release);
+                        for index in 0..512 {
+                            std::ptr::write_volatile(std::ptr::addr_of_mut!((*page).values[index]), version as u64);
+                        }
+                        fence(Ordering::Release);
+                        std::ptr::write_volatile(std::ptr::addr_of_mut!((*page).header.version_update_end), version);
+                    }
+                }
+                version
+            })
+        };
+
+        let (mut ok, mut retried, mut failed) = (0u32, 0u32, 0u32);
+        let until = std::time::Instant::now() + std::time::Duration::from_millis(500);
+        while std::time::Instant::now() < until {
+            match reader.snapshot() {
+                Ok((page, retries)) => {
+                    let version = page.header.version_update_begin;
+                    assert_eq!({ page.header.version_update_end }, version, "snapshot taken mid write");
+                    assert!(page.values.iter().all(|value| *value == version as u64), "torn snapshot of version {version}");
+                    ok += 1;
+                    retried += retries.min(1);
+                },
+                Err(attempts) => {
+                    assert_eq!(attempts, READ_RETRIES + 1);
+                    failed += 1;
+                },
             }
         }
+
+        stop.store(true, Ordering::Relaxed);
+        let written = writer.join().unwrap();
+        assert!(written > 1, "writer never ran");
+        assert!(ok > 0, "every snapshot failed ({failed} attempts)");
+        println!("{ok} snapshots ({retried} retried, {failed} failed) during {written} writes");
     }
 
-    /// This gives you a reference into the struct laying in the memory map
-    /// As such, against Rust guidelines, while holding this immutable reference
-    /// the data can be modified by the game, so it is a good idea to clone
-    pub fn get(&self) -> &T {
-        unsafe { &(*(self.memory as *const T)) }
+    #[test]
+    fn input_buffer_write_fills_a_copy() {
+        let shm = LocalShm::new("input_buffer_copy", size_of::<PageHWControl>());
+        let buffer = InputBuffer::<PageHWControl>::connect(&shm.name).unwrap();
+
+        buffer.write(HW_CONTROL_LAYOUT_VERSION, |page| page.ret_val = 1.0);
+        buffer.write(HW_CONTROL_LAYOUT_VERSION, |page| {
+            // The copy starts out as the current content
+            assert_eq!({ page.ret_val }, 1.0);
+            assert_eq!({ page.header.version_update_end }, 1);
+
+            // Versions are ours to set, not fill's
+            page.header.version_update_begin = 100;
+            page.header.version_update_end = 100;
+            page.ret_val = 0.0;
+        });
+
+        let bytes = shm.bytes();
+        assert_eq!(u32_at(&bytes, 0), 2, "version_update_begin");
+        assert_eq!(u32_at(&bytes, 4), 2, "version_update_end");
+        assert_eq!(f64::from_le_bytes(bytes[108..116].try_into().unwrap()), 0.0);
     }
 }
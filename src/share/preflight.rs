@@ -0,0 +1,61 @@
+use super::GAMES;
+
+/// Result of every setup check, Err contains a short hint on how to fix it
+#[derive(Debug, Clone)]
+pub(crate) struct Preflight {
+    pub protontricks: Result<(), &'static str>,
+    pub steam: Result<(), &'static str>,
+    /// Checks of each game, in the order of GAMES
+    pub games: [GameChecks; GAMES.len()],
+}
+
+impl Preflight {
+    /// Shared checks passed, and at least one game is fully set up
+    pub(crate) fn all_ok(&self) -> bool {
+        self.checks().iter().all(|(_, res)| res.is_ok())
+            && self.games.iter().any(GameChecks::all_ok)
+    }
+
+    /// Name and result of each check shared by all games
+    pub(crate) fn checks(&self) -> [(&'static str, Result<(), &'static str>); 2] {
+        [
+            ("protontricks", self.protontricks),
+            ("steam", self.steam),
+        ]
+    }
+}
+
+/// Setup checks of a single game, as each game has its own prefix and install
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GameChecks {
+    pub prefix: Result<(), &'static str>,
+    pub bridge: Result<(), &'static str>,
+    pub plugin_dll: Result<(), &'static str>,
+    pub plugin_enabled: Result<(), &'static str>,
+}
+
+impl GameChecks {
+    pub(crate) fn all_ok(&self) -> bool {
+        self.checks().iter().all(|(_, res)| res.is_ok())
+    }
+
+    /// The bridge can be launched in the prefix, without the plugin it connects but the pages stay empty
+    pub(crate) fn ready(&self) -> bool {
+        self.prefix.is_ok() && self.bridge.is_ok()
+    }
+
+    /// Name and result of each check
+    pub(crate) fn checks(&self) -> [(&'static str, Result<(), &'static str>); 4] {
+        [
+            ("prefix", self.prefix),
+            ("bridge", self.bridge),
+            ("plugin_dll", self.plugin_dll),
+            ("plugin_enabled", self.plugin_enabled),
+        ]
+    }
+}
+
+/// Turns a found/not found into a check result
+pub(super) fn check(ok: bool, hint: &'static str) -> Result<(), &'static str> {
+    if ok { Ok(()) } else { Err(hint) }
+}